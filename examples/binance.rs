@@ -21,24 +21,72 @@ use futures::{prelude::stream::StreamExt, SinkExt};
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
 use ratatui::prelude::*;
-use tui_candlestick_chart::{Candle, CandleStickChart, CandleStickChartState, Interval};
+use tui_candlestick_chart::{
+    AsyncCandleSource, Candle, CandleStickChart, CandleStickChartState, HistoryRequest,
+    HistoryRequestId, Interval, Keymap,
+};
+
+const INTERVAL: Interval = Interval::OneMinute;
+
+/// history requests done by the spawned fetches, `None` on failure
+type FinishedRequests = Rc<RefCell<Vec<(HistoryRequestId, Option<usize>)>>>;
 
 struct App {
-    is_loading_previous_candles: Rc<RefCell<bool>>,
     candles: Rc<RefCell<BTreeMap<i64, Candle>>>,
+    finished_requests: FinishedRequests,
     state: CandleStickChartState,
+    keymap: Keymap,
 }
 
 impl App {
     fn new() -> Self {
         Self {
-            is_loading_previous_candles: Rc::new(RefCell::new(false)),
             candles: Rc::new(RefCell::new(BTreeMap::new())),
+            finished_requests: Rc::new(RefCell::new(Vec::new())),
             state: CandleStickChartState::default(),
+            keymap: Keymap::default(),
         }
     }
 }
 
+struct BinanceKlines;
+
+impl AsyncCandleSource for BinanceKlines {
+    type Error = Box<dyn Error>;
+
+    async fn fetch(&self, request: &HistoryRequest) -> Result<Vec<Candle>, Self::Error> {
+        let bytes = Client::new()
+            .get(format!(
+                "https://fapi.binance.com/fapi/v1/klines?symbol=BTCUSDT&interval={}&startTime={}&endTime={}&limit=1500",
+                request.interval,
+                request.start,
+                request.end - 1
+            ))
+            .send()
+            .await?
+            .body()
+            .await?;
+        let json: serde_json::Value = serde_json::from_slice(&bytes)?;
+
+        let mut candles = Vec::new();
+        for kline in json.as_array().ok_or("unexpected response")? {
+            let data = kline.as_array().ok_or("unexpected kline")?;
+            let price = |i: usize| -> Result<f64, Box<dyn Error>> {
+                Ok(data[i].as_str().ok_or("unexpected price")?.parse::<f64>()?)
+            };
+            let timestamp = data[0].as_i64().ok_or("unexpected timestamp")?;
+            candles.push(Candle::try_new(
+                timestamp,
+                price(1)?,
+                price(2)?,
+                price(3)?,
+                price(4)?,
+            )?);
+        }
+        Ok(candles)
+    }
+}
+
 #[actix_rt::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     // setup terminal
@@ -81,30 +129,36 @@ async fn run_app<B: Backend>(
     loop {
         terminal.draw(|f| ui(f, &mut app))?;
 
-        if !*app.is_loading_previous_candles.borrow() {
-            let first_timestamp = app.candles.borrow().keys().next().cloned();
-            if app.state.is_needed_previous_candles() {
-                if let Some(first_timestamp) = first_timestamp {
-                    *app.is_loading_previous_candles.borrow_mut() = true;
-                    actix_rt::spawn(binance_btc_usdt_perp_klines(
-                        app.is_loading_previous_candles.clone(),
-                        first_timestamp,
-                        app.candles.clone(),
-                    ));
-                }
+        for (id, received) in app.finished_requests.borrow_mut().drain(..) {
+            match received {
+                Some(received) => app.state.finish_history_request(id, received),
+                None => app.state.cancel_history_request(id),
             }
         }
+        if let Some(request) = app.state.next_history_request() {
+            let candles = app.candles.clone();
+            let finished_requests = app.finished_requests.clone();
+            actix_rt::spawn(async move {
+                let received = BinanceKlines.fetch(&request).await.ok().map(|received| {
+                    let count = received.len();
+                    candles
+                        .borrow_mut()
+                        .extend(received.into_iter().map(|c| (c.timestamp, c)));
+                    count
+                });
+                finished_requests.borrow_mut().push((request.id, received));
+            });
+        }
 
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Left => app.state.try_move_backward(),
-                    KeyCode::Right => app.state.try_move_forward(),
-                    _ => {}
+            let event = event::read()?;
+            if let Event::Key(key) = &event {
+                if key.code == KeyCode::Char('q') {
+                    return Ok(());
                 }
             }
+            app.state.handle_event(&event, &app.keymap);
         }
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
@@ -128,7 +182,7 @@ async fn binance_btc_usdt_perp_agg_trade(candles: Rc<RefCell<BTreeMap<i64, Candl
         let response = connection.next().await.unwrap().unwrap();
         let ws::Frame::Text(bytes) = response else {
             if let ws::Frame::Ping(_) = response {
-                let _ = connection
+                connection
                     .send(ws::Message::Pong(([0x0A].as_slice()).into()))
                     .await
                     .unwrap();
@@ -136,9 +190,9 @@ async fn binance_btc_usdt_perp_agg_trade(candles: Rc<RefCell<BTreeMap<i64, Candl
             continue;
         };
         let json: serde_json::Value =
-            serde_json::from_str(&std::str::from_utf8(&bytes.to_vec()).unwrap()).unwrap();
+            serde_json::from_str(std::str::from_utf8(&bytes).unwrap()).unwrap();
 
-        let t = json["T"].as_i64().unwrap() / 60_000 * 60_000;
+        let t = INTERVAL.align(json["T"].as_i64().unwrap());
         let p = OrderedFloat::from(json["p"].as_str().unwrap().parse::<f64>().unwrap());
         candles
             .borrow_mut()
@@ -152,44 +206,8 @@ async fn binance_btc_usdt_perp_agg_trade(candles: Rc<RefCell<BTreeMap<i64, Candl
     }
 }
 
-async fn binance_btc_usdt_perp_klines(
-    is_loading_previous_candles: Rc<RefCell<bool>>,
-    first_timestamp: i64,
-    candles: Rc<RefCell<BTreeMap<i64, Candle>>>,
-) {
-    let bytes = Client::new()
-        .get(format!(
-            "https://fapi.binance.com/fapi/v1/klines?symbol=BTCUSDT&interval=1m&endTime={}",
-            first_timestamp
-        ))
-        .send()
-        .await
-        .unwrap()
-        .body()
-        .await
-        .unwrap();
-    let json: serde_json::Value =
-        serde_json::from_str(&std::str::from_utf8(&bytes.to_vec()).unwrap()).unwrap();
-
-    let mut candles = candles.borrow_mut();
-    for kline in json.as_array().unwrap() {
-        let data = kline.as_array().unwrap();
-        let timestamp = data[0].as_i64().unwrap();
-        let candle = Candle::new(
-            timestamp,
-            data[1].as_str().unwrap().parse::<f64>().unwrap(),
-            data[2].as_str().unwrap().parse::<f64>().unwrap(),
-            data[3].as_str().unwrap().parse::<f64>().unwrap(),
-            data[4].as_str().unwrap().parse::<f64>().unwrap(),
-        )
-        .unwrap();
-        candles.insert(timestamp, candle);
-    }
-    *is_loading_previous_candles.borrow_mut() = false;
-}
-
 fn ui(f: &mut Frame, app: &mut App) {
-    let chart = CandleStickChart::new(Interval::OneMinute)
+    let chart = CandleStickChart::new(INTERVAL)
         .candles(
             app.candles
                 .borrow()
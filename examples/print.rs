@@ -0,0 +1,21 @@
+use tui_candlestick_chart::{render_to_ansi_string, Candle, CandleStickChart, Interval};
+
+fn main() {
+    let candles = vec![
+        Candle::new(1703976900000, 42184.08, 42191.01, 42184.07, 42191.01).unwrap(),
+        Candle::new(1703976960000, 42191.01, 42191.01, 42190.96, 42190.97).unwrap(),
+        Candle::new(1703977020000, 42190.96, 42190.97, 42123.95, 42124.50).unwrap(),
+        Candle::new(1703977080000, 42124.51, 42161.10, 42124.50, 42158.65).unwrap(),
+        Candle::new(1703977140000, 42158.65, 42158.65, 42079.97, 42132.73).unwrap(),
+        Candle::new(1703977200000, 42132.73, 42161.40, 42102.88, 42133.85).unwrap(),
+        Candle::new(1703977260000, 42133.84, 42162.37, 42118.04, 42141.10).unwrap(),
+        Candle::new(1703977320000, 42141.11, 42174.66, 42127.08, 42168.24).unwrap(),
+        Candle::new(1703977380000, 42168.25, 42197.54, 42161.61, 42197.53).unwrap(),
+        Candle::new(1703977440000, 42197.53, 42210.86, 42182.83, 42189.22).unwrap(),
+        Candle::new(1703977500000, 42189.22, 42220.42, 42189.22, 42220.41).unwrap(),
+        Candle::new(1703977560000, 42220.42, 42231.10, 42220.41, 42220.52).unwrap(),
+    ];
+
+    let chart = CandleStickChart::new(Interval::OneMinute).candles(candles);
+    println!("{}", render_to_ansi_string(chart, 60, 20));
+}
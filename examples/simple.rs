@@ -10,11 +10,17 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
-use tui_candlestick_chart::{Candle, CandleStickChart, CandleStickChartState, Interval};
+use tui_candlestick_chart::{
+    Candle, CandleStickChart, CandleStickChartState, Interval, Keymap, Theme,
+};
 
 struct App {
     candles: Vec<Candle>,
     state: CandleStickChartState,
+    themes: Vec<Theme>,
+    theme_index: usize,
+    grid: bool,
+    keymap: Keymap,
 }
 
 impl App {
@@ -523,6 +529,15 @@ impl App {
                 Candle::new(1704006840000, 42313.99, 42314.00, 42313.99, 42313.99).unwrap(),
             ],
             state: CandleStickChartState::default(),
+            themes: vec![
+                Theme::dark(),
+                Theme::light(),
+                Theme::colorblind(),
+                Theme::monochrome(),
+            ],
+            theme_index: 0,
+            grid: false,
+            keymap: Keymap::default(),
         }
     }
 }
@@ -567,14 +582,20 @@ fn run_app<B: Backend>(
 
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
+            let event = event::read()?;
+            if let Event::Key(key) = event {
                 match key.code {
                     KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Left => app.state.try_move_backward(),
-                    KeyCode::Right => app.state.try_move_forward(),
+                    KeyCode::Char('g') => app.grid = !app.grid,
+                    KeyCode::Char('m') if app.state.is_measuring() => app.state.stop_measure(),
+                    KeyCode::Char('m') => app.state.start_measure(),
+                    KeyCode::Char('t') => {
+                        app.theme_index = (app.theme_index + 1) % app.themes.len()
+                    }
                     _ => {}
                 }
             }
+            app.state.handle_event(&event, &app.keymap);
         }
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
@@ -583,6 +604,9 @@ fn run_app<B: Backend>(
 }
 
 fn ui(f: &mut Frame, app: &mut App) {
-    let chart = CandleStickChart::new(Interval::OneMinute).candles(app.candles.clone());
+    let chart = CandleStickChart::new(Interval::OneMinute)
+        .candles(app.candles.clone())
+        .theme(app.themes[app.theme_index])
+        .grid(app.grid);
     f.render_stateful_widget(chart, f.size(), &mut app.state);
 }
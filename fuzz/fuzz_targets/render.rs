@@ -0,0 +1,81 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::StatefulWidget};
+use tui_candlestick_chart::{
+    Anchor, Candle, CandleStickChart, CandleStickChartState, DrawingKind, Interval, Marker,
+    PriceLevel, YAxisPlacement,
+};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    candles: Vec<(i64, f64, f64, f64, f64)>,
+    interval: u8,
+    placement: u8,
+    area: (u16, u16, u16, u16),
+    level: f64,
+    marker: i64,
+    drawing: (i64, f64, i64, f64),
+    lock: Option<(f64, f64)>,
+    actions: Vec<u8>,
+}
+
+fuzz_target!(|input: Input| {
+    let candles = input
+        .candles
+        .into_iter()
+        .map(|(timestamp, open, high, low, close)| Candle {
+            timestamp,
+            open: open.into(),
+            high: high.into(),
+            low: low.into(),
+            close: close.into(),
+        })
+        .collect();
+    let interval = Interval::iter()
+        .nth(input.interval as usize % Interval::iter().count())
+        .unwrap();
+    let placement = match input.placement % 3 {
+        0 => YAxisPlacement::Left,
+        1 => YAxisPlacement::Right,
+        _ => YAxisPlacement::Both,
+    };
+    let chart = CandleStickChart::new(interval)
+        .candles(candles)
+        .y_axis_placement(placement)
+        .grid(true)
+        .price_levels(vec![PriceLevel::new(input.level).show_offscreen(true)])
+        .markers(vec![Marker::buy(input.marker), Marker::sell(input.marker)]);
+
+    let mut state = CandleStickChartState::default();
+    if let Some((min, max)) = input.lock {
+        state.lock_y_range(min, max);
+    }
+    let (t0, p0, t1, p1) = input.drawing;
+    state.add_drawing(
+        DrawingKind::TrendLine,
+        Anchor::new(t0, p0),
+        Anchor::new(t1, p1),
+        Color::Red,
+    );
+
+    // the area may exceed the buffer
+    let mut buffer = Buffer::empty(Rect::new(0, 0, 120, 50));
+    let (x, y, width, height) = input.area;
+    let area = Rect::new(x % 200, y % 100, width % 300, height % 100);
+    chart.clone().render(area, &mut buffer, &mut state);
+    for action in input.actions.into_iter().take(32) {
+        match action % 8 {
+            0 => state.try_move_backward(),
+            1 => state.try_move_forward(),
+            2 => state.try_move_up(),
+            3 => state.zoom_y_in(),
+            4 => state.zoom_y_out(),
+            5 => state.start_measure(),
+            6 => state.reset_cursor(),
+            _ => state.reset_y_range(),
+        }
+        chart.clone().render(area, &mut buffer, &mut state);
+    }
+});
@@ -0,0 +1,44 @@
+use std::cmp::Reverse;
+
+use tui_candlestick_chart::{Candle, Interval};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Csv,
+    Json,
+}
+
+impl Format {
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" | "tsv" | "txt" => Some(Format::Csv),
+            "json" | "jsonl" | "ndjson" => Some(Format::Json),
+            _ => None,
+        }
+    }
+}
+
+/// a tab for .tsv files, `,` otherwise
+pub fn default_delimiter(path: Option<&str>) -> u8 {
+    match path.and_then(|path| path.rsplit_once('.')) {
+        Some((_, extension)) if extension.eq_ignore_ascii_case("tsv") => b'\t',
+        _ => b',',
+    }
+}
+
+/// the most frequent gap between candles
+pub fn infer_interval(candles: &[Candle]) -> Option<Interval> {
+    let mut gaps = std::collections::HashMap::new();
+    // gaps which overflow, e.g. of saturated timestamps, are skipped
+    for (a, b) in candles.iter().zip(candles.iter().skip(1)) {
+        if let Some(gap) = b.timestamp.checked_sub(a.timestamp) {
+            *gaps.entry(gap).or_insert(0) += 1;
+        }
+    }
+    let (gap, _) = gaps
+        .into_iter()
+        .max_by_key(|(gap, count)| (*count, Reverse(*gap)))?;
+
+    Interval::iter().find(|interval| interval.as_millis() == gap)
+}
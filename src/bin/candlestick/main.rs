@@ -0,0 +1,261 @@
+mod input;
+
+use std::{
+    error::Error,
+    fs,
+    io::{self, IsTerminal, Read},
+    time::{Duration, Instant},
+};
+
+use clap::Parser;
+use crossterm::{
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::prelude::*;
+use tui_candlestick_chart::{
+    load_csv, load_json, render_to_ansi_string, render_to_string, Candle, CandleStickChart,
+    CandleStickChartState, Column, Interval, Keymap, LoadOptions, Replay, Theme, TimestampFormat,
+};
+
+use crate::input::Format;
+
+/// Chart OHLC candles from a CSV or JSON file
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// input file, stdin if omitted or `-`
+    file: Option<String>,
+
+    /// input format, inferred from the file extension, csv for stdin
+    #[arg(short, long, value_enum)]
+    format: Option<Format>,
+
+    /// candle interval, e.g. 1m, 4h, 1d, inferred from the timestamps if omitted
+    #[arg(short, long)]
+    interval: Option<Interval>,
+
+    /// print the chart once instead of opening the viewer
+    #[arg(short, long)]
+    print: bool,
+
+    /// width of the printed chart, the terminal width if omitted
+    #[arg(long, requires = "print")]
+    width: Option<u16>,
+
+    /// height of the printed chart, the terminal height if omitted
+    #[arg(long, requires = "print")]
+    height: Option<u16>,
+
+    /// print without colors
+    #[arg(long, requires = "print")]
+    plain: bool,
+
+    /// timestamp column, header name / object key or zero based index
+    #[arg(long, value_name = "COLUMN")]
+    timestamp: Option<Column>,
+
+    /// open column
+    #[arg(long, value_name = "COLUMN")]
+    open: Option<Column>,
+
+    /// high column
+    #[arg(long, value_name = "COLUMN")]
+    high: Option<Column>,
+
+    /// low column
+    #[arg(long, value_name = "COLUMN")]
+    low: Option<Column>,
+
+    /// close column
+    #[arg(long, value_name = "COLUMN")]
+    close: Option<Column>,
+
+    /// `auto`, `s`, `ms` or a chrono format string like `%Y-%m-%d %H:%M`
+    #[arg(long, default_value = "auto")]
+    timestamp_format: TimestampFormat,
+
+    /// csv field delimiter, a tab for .tsv files and `,` otherwise
+    #[arg(long, value_parser = parse_delimiter)]
+    delimiter: Option<u8>,
+
+    /// the first csv line is data
+    #[arg(long)]
+    no_header: bool,
+
+    /// clamp open and close into high and low instead of skipping the row
+    #[arg(long)]
+    repair: bool,
+
+    /// play the candles back at the speed, 1 to 100 times real time
+    #[arg(long, value_name = "SPEED", conflicts_with = "print")]
+    replay: Option<f64>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    let (input, format) = match args.file.as_deref() {
+        None | Some("-") => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            (input, args.format.unwrap_or(Format::Csv))
+        }
+        Some(path) => {
+            let format = args
+                .format
+                .or_else(|| Format::from_path(path))
+                .ok_or("cannot infer the format from the file extension, use --format")?;
+            (fs::read_to_string(path)?, format)
+        }
+    };
+
+    let mut options = LoadOptions::default()
+        .timestamp_format(args.timestamp_format)
+        .delimiter(
+            args.delimiter
+                .unwrap_or_else(|| input::default_delimiter(args.file.as_deref())),
+        )
+        .has_header(!args.no_header)
+        .repair(args.repair);
+    let columns = [args.timestamp, args.open, args.high, args.low, args.close];
+    let setters = [
+        LoadOptions::timestamp_column,
+        LoadOptions::open_column,
+        LoadOptions::high_column,
+        LoadOptions::low_column,
+        LoadOptions::close_column,
+    ];
+    for (column, setter) in columns.into_iter().zip(setters) {
+        if let Some(column) = column {
+            options = setter(options, column);
+        }
+    }
+
+    let report = match format {
+        Format::Csv => load_csv(input.as_bytes(), &options)?,
+        Format::Json => load_json(&input, &options)?,
+    };
+    if !report.is_clean() {
+        eprintln!("{}", report);
+    }
+    let candles = report.candles;
+    if candles.is_empty() {
+        return Err("no candles".into());
+    }
+
+    let interval = match args.interval {
+        Some(interval) => interval,
+        None => input::infer_interval(&candles)
+            .ok_or("cannot infer the interval from the timestamps, use --interval")?,
+    };
+
+    if args.print {
+        let (columns, rows) = crossterm::terminal::size().unwrap_or((80, 24));
+        let width = args.width.unwrap_or(columns);
+        let height = args.height.unwrap_or(rows.saturating_sub(1));
+        let chart = CandleStickChart::new(interval).candles(candles);
+        if args.plain || !io::stdout().is_terminal() {
+            println!("{}", render_to_string(chart, width, height));
+        } else {
+            println!("{}", render_to_ansi_string(chart, width, height));
+        }
+        return Ok(());
+    }
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let replay = args.replay.map(|speed| {
+        let mut replay = Replay::from_candles(candles.clone(), interval, interval);
+        replay.set_speed(speed);
+        replay
+    });
+    let res = run(&mut terminal, interval, candles, replay);
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    Ok(res?)
+}
+
+fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    interval: Interval,
+    candles: Vec<Candle>,
+    mut replay: Option<Replay>,
+) -> io::Result<()> {
+    let mut state = CandleStickChartState::default();
+    let keymap = Keymap::default();
+    let themes = [
+        Theme::dark(),
+        Theme::light(),
+        Theme::colorblind(),
+        Theme::monochrome(),
+    ];
+    let mut theme_index = 0;
+    let mut grid = false;
+    let mut last_frame = Instant::now();
+
+    loop {
+        if let Some(replay) = &mut replay {
+            replay.advance(last_frame.elapsed());
+        }
+        last_frame = Instant::now();
+
+        terminal.draw(|f| {
+            let chart = CandleStickChart::new(interval)
+                .theme(themes[theme_index])
+                .grid(grid);
+            let chart = match &replay {
+                Some(replay) => chart.candles(replay.candles().to_vec()).clock(replay),
+                None => chart.candles(candles.clone()),
+            };
+            f.render_stateful_widget(chart, f.size(), &mut state);
+        })?;
+
+        let timeout = if replay.is_some() { 50 } else { 250 };
+        if !event::poll(Duration::from_millis(timeout))? {
+            continue;
+        }
+        let event = event::read()?;
+        if let Event::Key(key) = event {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('g') => grid = !grid,
+                KeyCode::Char('m') if state.is_measuring() => state.stop_measure(),
+                KeyCode::Char('m') => state.start_measure(),
+                KeyCode::Char('t') => theme_index = (theme_index + 1) % themes.len(),
+                KeyCode::Char(' ') => replay.iter_mut().for_each(Replay::toggle_pause),
+                KeyCode::Char('.') => replay.iter_mut().for_each(Replay::step),
+                KeyCode::Char('>') => replay
+                    .iter_mut()
+                    .for_each(|replay| replay.set_speed(replay.speed() * 2.)),
+                KeyCode::Char('<') => replay
+                    .iter_mut()
+                    .for_each(|replay| replay.set_speed(replay.speed() / 2.)),
+                _ => {}
+            }
+        }
+        state.handle_event(&event, &keymap);
+    }
+}
+
+fn parse_delimiter(s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        [delimiter] => Ok(*delimiter),
+        _ if s == "\\t" => Ok(b'\t'),
+        _ => Err("expected a single ascii character".to_string()),
+    }
+}
@@ -1,16 +1,25 @@
-use std::cmp::{max, min};
+use std::{
+    cmp::{max, min},
+    error::Error,
+    fmt,
+};
 
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
 
 use crate::{symbols::*, y_axis::YAxis, Float};
 
+/// about ±250,000 years, within chrono's range with room for scrolling past the candles
+pub(crate) const MAX_TIMESTAMP: i64 = 8_000_000_000_000_000;
+pub(crate) const MIN_TIMESTAMP: i64 = -MAX_TIMESTAMP;
+
 pub(crate) enum CandleType {
     Bearish,
     Bullish,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Candle {
     pub timestamp: i64,
     pub open: Float,
@@ -20,20 +29,78 @@ pub struct Candle {
 }
 
 impl Candle {
+    /// only checks `high >= low`, see `try_new` for the full validation
     pub fn new(timestamp: i64, open: f64, high: f64, low: f64, close: f64) -> Option<Self> {
         if high >= low {
-            Some(Self {
-                timestamp,
-                open: OrderedFloat::from(open),
-                high: OrderedFloat::from(high),
-                low: OrderedFloat::from(low),
-                close: OrderedFloat::from(close),
-            })
+            Some(Self::new_unchecked(timestamp, open, high, low, close))
         } else {
             None
         }
     }
 
+    /// all prices are finite, `high >= low`, and open and close are within high and low
+    pub fn try_new(
+        timestamp: i64,
+        open: f64,
+        high: f64,
+        low: f64,
+        close: f64,
+    ) -> Result<Self, CandleError> {
+        check_high_low(open, high, low, close)?;
+        if !(low..=high).contains(&open) {
+            return Err(CandleError::OpenOutOfRange { open, high, low });
+        }
+        if !(low..=high).contains(&close) {
+            return Err(CandleError::CloseOutOfRange { close, high, low });
+        }
+
+        Ok(Self::new_unchecked(timestamp, open, high, low, close))
+    }
+
+    /// like `try_new`, but open and close are clamped into high and low instead of rejected
+    pub fn try_new_repaired(
+        timestamp: i64,
+        open: f64,
+        high: f64,
+        low: f64,
+        close: f64,
+    ) -> Result<Self, CandleError> {
+        check_high_low(open, high, low, close)?;
+
+        Ok(Self::new_unchecked(
+            timestamp,
+            open.clamp(low, high),
+            high,
+            low,
+            close.clamp(low, high),
+        ))
+    }
+
+    fn new_unchecked(timestamp: i64, open: f64, high: f64, low: f64, close: f64) -> Self {
+        Self {
+            timestamp,
+            open: OrderedFloat::from(open),
+            high: OrderedFloat::from(high),
+            low: OrderedFloat::from(low),
+            close: OrderedFloat::from(close),
+        }
+    }
+
+    /// finite prices, `high >= low` and a timestamp which can be shown on the x axis, fields are
+    /// public so candles are not necessarily built by the checked constructors
+    pub(crate) fn is_renderable(&self) -> bool {
+        [self.open, self.high, self.low, self.close]
+            .iter()
+            .all(|price| price.is_finite())
+            && self.high >= self.low
+            && Self::is_renderable_timestamp(self.timestamp)
+    }
+
+    /// far enough from the ends of `i64` to be aligned and shown on the x axis
+    pub(crate) fn is_renderable_timestamp(timestamp: i64) -> bool {
+        (MIN_TIMESTAMP..=MAX_TIMESTAMP).contains(&timestamp)
+    }
+
     pub(crate) fn render(&self, y_axis: &YAxis) -> (CandleType, Vec<&str>) {
         let open = y_axis.calc_y(self.open);
         let close = y_axis.calc_y(self.close);
@@ -110,8 +177,9 @@ impl Candle {
             result.push(char);
         }
 
+        // candles out of the (locked) y range are not drawn at all
         #[cfg(debug_assertions)]
-        if !test_continuous_graph(result.clone()) {
+        if high >= 0. && low <= y_axis.height() as f64 && !test_continuous_graph(result.clone()) {
             tracing::error!("The result of candle rendering is broken. Please report it.")
         }
 
@@ -125,6 +193,70 @@ impl Candle {
     }
 }
 
+fn check_high_low(open: f64, high: f64, low: f64, close: f64) -> Result<(), CandleError> {
+    for (field, value) in [
+        ("open", open),
+        ("high", high),
+        ("low", low),
+        ("close", close),
+    ] {
+        if !value.is_finite() {
+            return Err(CandleError::NonFinite { field, value });
+        }
+    }
+    if high < low {
+        return Err(CandleError::HighBelowLow { high, low });
+    }
+    Ok(())
+}
+
+/// Which invariant of a candle is violated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CandleError {
+    /// NaN or infinite
+    NonFinite {
+        field: &'static str,
+        value: f64,
+    },
+    HighBelowLow {
+        high: f64,
+        low: f64,
+    },
+    OpenOutOfRange {
+        open: f64,
+        high: f64,
+        low: f64,
+    },
+    CloseOutOfRange {
+        close: f64,
+        high: f64,
+        low: f64,
+    },
+}
+
+impl fmt::Display for CandleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CandleError::NonFinite { field, value } => write!(f, "{} is {}", field, value),
+            CandleError::HighBelowLow { high, low } => {
+                write!(f, "high {} is below low {}", high, low)
+            }
+            CandleError::OpenOutOfRange { open, high, low } => {
+                write!(f, "open {} is outside low {} and high {}", open, low, high)
+            }
+            CandleError::CloseOutOfRange { close, high, low } => {
+                write!(
+                    f,
+                    "close {} is outside low {} and high {}",
+                    close, low, high
+                )
+            }
+        }
+    }
+}
+
+impl Error for CandleError {}
+
 fn test_continuous_graph(mut chars: Vec<&str>) -> bool {
     if chars.iter().all(|&c| c == UNICODE_VOID) {
         return false;
@@ -169,3 +301,40 @@ fn test_continuous_graph(mut chars: Vec<&str>) -> bool {
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Candle, CandleError};
+
+    #[test]
+    fn test_try_new() {
+        assert!(Candle::try_new(0, 1., 2., 0., 1.5).is_ok());
+        assert!(matches!(
+            Candle::try_new(0, 1., f64::NAN, 0., 1.),
+            Err(CandleError::NonFinite { field: "high", .. })
+        ));
+        assert_eq!(
+            Candle::try_new(0, 1., 0., 2., 1.),
+            Err(CandleError::HighBelowLow { high: 0., low: 2. })
+        );
+        assert_eq!(
+            Candle::try_new(0, 3., 2., 0., 1.),
+            Err(CandleError::OpenOutOfRange {
+                open: 3.,
+                high: 2.,
+                low: 0.
+            })
+        );
+        assert_eq!(
+            Candle::try_new(0, 1., 2., 0., -1.).unwrap_err().to_string(),
+            "close -1 is outside low 0 and high 2"
+        );
+
+        assert_eq!(
+            Candle::try_new_repaired(0, 3., 2., 0., -1.),
+            Ok(Candle::new(0, 2., 2., 0., 0.).unwrap())
+        );
+        assert!(Candle::try_new_repaired(0, 1., 0., 2., 1.).is_err());
+        assert!(Candle::try_new_repaired(0, f64::INFINITY, 2., 0., 1.).is_err());
+    }
+}
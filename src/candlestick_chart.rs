@@ -1,17 +1,27 @@
+use std::collections::HashSet;
+
 use chrono::{FixedOffset, Offset, Utc};
 use itertools::Itertools;
 use ratatui::{
     prelude::{Buffer, Rect},
-    style::{Color, Style, Styled},
+    style::{Color, Modifier, Style, Styled},
     widgets::StatefulWidget,
 };
 
 use crate::{
     candle::{Candle, CandleType},
     candlestick_chart_state::CandleStikcChartInfo,
+    comparison::{Comparison, ScaledComparison},
+    marker::{Marker, MarkerSide},
+    measure::Measurement,
+    normalize::{normalize_candles, DuplicatePolicy},
+    price_level::PriceLevel,
+    projection::Projection,
+    symbols::*,
+    theme::Theme,
     x_axis::{Interval, XAxis},
-    y_axis::{Numeric, YAxis},
-    CandleStickChartState,
+    y_axis::{AutoFit, Numeric, Side, YAxis, YAxisPlacement},
+    Anchor, CandleStickChartState, Clock, Float, SystemClock,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -24,11 +34,32 @@ pub struct CandleStickChart {
     numeric: Numeric,
     /// Widget style
     style: Style,
-    /// Candle style,
-    bearish_color: Color,
-    bullish_color: Color,
+    /// Colors of candles, axes and labels
+    theme: Theme,
+    /// draw grid lines at axis ticks
+    grid: bool,
+    /// y axis side(s)
+    y_axis_placement: YAxisPlacement,
+    /// y range fitting while the range is not locked by the state
+    auto_fit: AutoFit,
+    /// horizontal lines at prices
+    price_levels: Vec<PriceLevel>,
+    /// glyphs on candles
+    markers: Vec<Marker>,
+    /// lines of other series on a percent y axis
+    comparisons: Vec<Comparison>,
     /// display timezone
     display_timezone: FixedOffset,
+    /// which candle is drawn for a repeated timestamp
+    duplicate_policy: DuplicatePolicy,
+    /// skip candles not starting at a bucket of the interval
+    drop_unaligned: bool,
+    /// fills columns without a candle between the first and the last candle
+    gap_symbol: Option<String>,
+    /// shown instead of the chart without any candle, nothing if empty
+    empty_message: String,
+    /// current time in milliseconds, the system time if `None`
+    now: Option<i64>,
 }
 
 impl CandleStickChart {
@@ -38,9 +69,19 @@ impl CandleStickChart {
             candles: Vec::default(),
             numeric: Numeric::default(),
             style: Style::default(),
-            bearish_color: Color::Rgb(234, 74, 90),
-            bullish_color: Color::Rgb(52, 208, 88),
+            theme: Theme::default(),
+            grid: false,
+            y_axis_placement: YAxisPlacement::default(),
+            auto_fit: AutoFit::default(),
+            price_levels: Vec::default(),
+            markers: Vec::default(),
+            comparisons: Vec::default(),
             display_timezone: Utc.fix(),
+            duplicate_policy: DuplicatePolicy::default(),
+            drop_unaligned: false,
+            gap_symbol: None,
+            empty_message: NO_DATA.to_string(),
+            now: None,
         }
     }
 
@@ -49,23 +90,60 @@ impl CandleStickChart {
         self
     }
 
+    pub fn price_levels(mut self, price_levels: Vec<PriceLevel>) -> Self {
+        self.price_levels = price_levels;
+        self
+    }
+
+    pub fn markers(mut self, markers: Vec<Marker>) -> Self {
+        self.markers = markers;
+        self
+    }
+
+    /// overlay the series as lines, the y axis shows the percent change since the first visible
+    /// candle
+    pub fn comparisons(mut self, comparisons: Vec<Comparison>) -> Self {
+        self.comparisons = comparisons;
+        self
+    }
+
     pub fn y_axis_numeric(mut self, numeric: Numeric) -> Self {
         self.numeric = numeric;
         self
     }
 
+    pub fn y_axis_placement(mut self, placement: YAxisPlacement) -> Self {
+        self.y_axis_placement = placement;
+        self
+    }
+
+    pub fn auto_fit(mut self, auto_fit: AutoFit) -> Self {
+        self.auto_fit = auto_fit;
+        self
+    }
+
     pub fn style(mut self, style: Style) -> Self {
         self.style = style;
         self
     }
 
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn grid(mut self, grid: bool) -> Self {
+        self.grid = grid;
+        self
+    }
+
     pub fn bearish_color(mut self, color: Color) -> Self {
-        self.bearish_color = color;
+        self.theme.bearish = color;
         self
     }
 
     pub fn bullish_color(mut self, color: Color) -> Self {
-        self.bullish_color = color;
+        self.theme.bullish = color;
         self
     }
 
@@ -73,6 +151,464 @@ impl CandleStickChart {
         self.display_timezone = offset;
         self
     }
+
+    pub fn duplicate_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = policy;
+        self
+    }
+
+    pub fn drop_unaligned(mut self, drop_unaligned: bool) -> Self {
+        self.drop_unaligned = drop_unaligned;
+        self
+    }
+
+    /// e.g. `"░"` to shade missing candles, drawn in the grid color
+    pub fn gap_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.gap_symbol = Some(symbol.into());
+        self
+    }
+
+    pub fn empty_message(mut self, message: impl Into<String>) -> Self {
+        self.empty_message = message.into();
+        self
+    }
+
+    /// current time in milliseconds instead of the system time, e.g. of a replay
+    pub fn now(mut self, now: i64) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// read the current time from the clock instead of the system time
+    pub fn clock(self, clock: &impl Clock) -> Self {
+        self.now(clock.now())
+    }
+}
+
+impl CandleStickChart {
+    /// write axis string, coloring axis lines, labels and the realtime marker separately, chars
+    /// out of the area are cut off
+    fn set_axis_string(&self, buf: &mut Buffer, area: Rect, x: i32, y: u16, string: &str) {
+        let style = Style::default().bg(self.theme.background);
+        for (i, char) in string.chars().enumerate() {
+            let x = x + i as i32;
+            if x < area.left() as i32 || x >= area.right() as i32 {
+                continue;
+            }
+            let fg = match char {
+                AXIS_REALTIME => self.theme.realtime,
+                c if AXIS_LINES.contains(c) => self.theme.axis,
+                _ => self.theme.label,
+            };
+            buf.get_mut(x as u16, y)
+                .set_char(char)
+                .set_style(style.fg(fg));
+        }
+    }
+
+    /// write the text at the middle row, horizontally centered and cut off by the area
+    fn render_centered(&self, buf: &mut Buffer, area: Rect, text: &str) {
+        if area.is_empty() {
+            return;
+        }
+        let width = text.chars().count() as u16;
+        buf.set_stringn(
+            area.x + area.width.saturating_sub(width) / 2,
+            area.y + area.height / 2,
+            text,
+            area.width as usize,
+            Style::default()
+                .fg(self.theme.label)
+                .bg(self.theme.background),
+        );
+    }
+
+    /// centered in the columns before the first candle, kept within the area, or at the top left
+    /// if they are scrolled out
+    fn render_loading(&self, buf: &mut Buffer, area: Rect, columns_before_first: u16) {
+        let width = (LOADING.chars().count() as u16).min(area.width);
+        let (x, y) = if columns_before_first > 0 {
+            let x = (area.x + columns_before_first / 2)
+                .saturating_sub(width / 2)
+                .clamp(area.x, area.right() - width);
+            (x, area.y + area.height / 2)
+        } else {
+            (area.x, area.y)
+        };
+        buf.set_stringn(
+            x,
+            y,
+            LOADING,
+            width as usize,
+            Style::default()
+                .fg(self.theme.label)
+                .bg(self.theme.background),
+        );
+    }
+
+    /// shade the columns without a candle between the first and the last drawn column, beneath
+    /// grid and price lines
+    fn render_gaps(&self, buf: &mut Buffer, area: Rect, columns: &[(u16, &Candle)]) {
+        let Some(symbol) = &self.gap_symbol else {
+            return;
+        };
+        let (Some(first), Some(last)) = (columns.first(), columns.last()) else {
+            return;
+        };
+        let style = Style::default()
+            .fg(self.theme.grid)
+            .bg(self.theme.background);
+        let drawn = columns
+            .iter()
+            .map(|(column, _)| *column)
+            .collect::<HashSet<_>>();
+        for column in (first.0..last.0).filter(|column| !drawn.contains(column)) {
+            for y in area.top()..area.bottom() {
+                let cell = buf.get_mut(area.x + column, y);
+                if !BACKGROUND_SYMBOLS.contains(&cell.symbol()) {
+                    cell.set_symbol(symbol).set_style(style);
+                }
+            }
+        }
+    }
+
+    /// draw dotted lines beneath candles at every y axis tick row and x axis `┴` column
+    fn render_grid(&self, buf: &mut Buffer, area: Rect, y_axis: &YAxis, x_ticks: &[usize]) {
+        let style = Style::default()
+            .fg(self.theme.grid)
+            .bg(self.theme.background)
+            .add_modifier(Modifier::DIM);
+        for row in 0..y_axis.height() {
+            let is_row_tick = y_axis.is_tick(row);
+            for &column in x_ticks {
+                let symbol = if is_row_tick {
+                    GRID_CROSS
+                } else {
+                    GRID_VERTICAL
+                };
+                buf.get_mut(area.x + column as u16, area.y + row)
+                    .set_symbol(symbol)
+                    .set_style(style);
+            }
+            if is_row_tick {
+                for column in 0..area.width {
+                    if !x_ticks.contains(&(column as usize)) {
+                        buf.get_mut(area.x + column, area.y + row)
+                            .set_symbol(GRID_HORIZONTAL)
+                            .set_style(style);
+                    }
+                }
+            }
+        }
+    }
+
+    /// draw comparisons over candles, breaking the line where a series has no price
+    fn render_comparisons(
+        &self,
+        buf: &mut Buffer,
+        projection: &Projection,
+        comparisons: &[ScaledComparison],
+    ) {
+        let interval = self.interval.as_millis();
+        for comparison in comparisons {
+            let style = Style::default()
+                .fg(comparison.color)
+                .bg(self.theme.background);
+            // prices at the top of the y range are on the first row like the highs of candles
+            let cell = |&(timestamp, price): &(i64, Float)| {
+                let row = projection
+                    .y_axis
+                    .calc_row(price)
+                    .map_or_else(|| projection.row(price), i64::from);
+                (projection.column(timestamp), row)
+            };
+            for (from, to) in comparison.points.iter().tuple_windows() {
+                if to.0 - from.0 == interval {
+                    for (x, y) in projection.line(cell(from), cell(to)) {
+                        buf.get_mut(x, y)
+                            .set_symbol(COMPARISON_LINE)
+                            .set_style(style);
+                    }
+                }
+            }
+            for point in &comparison.points {
+                let (column, row) = cell(point);
+                if let Some((x, y)) = projection.position(column, row) {
+                    buf.get_mut(x, y)
+                        .set_symbol(COMPARISON_POINT)
+                        .set_style(style);
+                }
+            }
+        }
+    }
+
+    /// name and percent change of each comparison at the top of the candles
+    fn render_legend(&self, buf: &mut Buffer, area: Rect, comparisons: &[ScaledComparison]) {
+        for (row, comparison) in comparisons.iter().enumerate() {
+            if row as u16 >= area.height {
+                break;
+            }
+            let change = comparison
+                .change
+                .map(|change| format!(" {:+.2}%", change))
+                .unwrap_or_default();
+            let legend = format!("{} {}{}", COMPARISON_LEGEND, comparison.name, change);
+            let width = (legend.chars().count() as u16).min(area.width);
+            let x = if self.y_axis_placement.has_left() {
+                area.left()
+            } else {
+                area.right() - width
+            };
+            buf.set_stringn(
+                x,
+                area.y + row as u16,
+                legend,
+                width as usize,
+                Style::default()
+                    .fg(comparison.color)
+                    .bg(self.theme.background),
+            );
+        }
+    }
+
+    /// draw price level lines beneath candles
+    fn render_price_lines(&self, buf: &mut Buffer, area: Rect, y_axis: &YAxis) {
+        for level in &self.price_levels {
+            if let Some(row) = y_axis.calc_row(level.price) {
+                let style = Style::default().fg(level.color).bg(self.theme.background);
+                for x in area.left()..area.right() {
+                    buf.get_mut(x, area.y + row)
+                        .set_symbol(level.line_style.symbol())
+                        .set_style(style);
+                }
+            }
+        }
+    }
+
+    /// draw crosshair lines beneath candles, and the price on the y axis
+    fn render_crosshair(
+        &self,
+        buf: &mut Buffer,
+        projection: &Projection,
+        crosshair: Anchor,
+        value_width: u16,
+    ) {
+        let area = projection.area;
+        let style = Style::default()
+            .fg(self.theme.crosshair)
+            .bg(self.theme.background);
+        let column = projection.column(crosshair.timestamp);
+        let row = projection.row(crosshair.price);
+        for y in 0..area.height as i64 {
+            if let Some((x, y)) = projection.position(column, y) {
+                buf.get_mut(x, y)
+                    .set_symbol(CROSSHAIR_VERTICAL)
+                    .set_style(style);
+            }
+        }
+        for x in 0..area.width as i64 {
+            if let Some((x, y)) = projection.position(x, row) {
+                let symbol = if x as i64 - area.x as i64 == column {
+                    CROSSHAIR_CROSS
+                } else {
+                    CROSSHAIR_HORIZONTAL
+                };
+                buf.get_mut(x, y).set_symbol(symbol).set_style(style);
+            }
+        }
+
+        if !(0..area.height as i64).contains(&row) {
+            return;
+        }
+        let value = format!(
+            "{:>width$}",
+            projection.y_axis.format(crosshair.price),
+            width = value_width as usize
+        );
+        let style = style.add_modifier(Modifier::REVERSED);
+        let y = area.y + row as u16;
+        if self.y_axis_placement.has_left() {
+            buf.set_string(area.left() - value_width - 3, y, &value, style);
+        }
+        if self.y_axis_placement.has_right() {
+            buf.set_string(area.right() + 3, y, &value, style);
+        }
+    }
+
+    /// draw price level labels over candles next to the y axis, and their values on the y axis
+    fn render_price_labels(&self, buf: &mut Buffer, area: Rect, y_axis: &YAxis, value_width: u16) {
+        for level in &self.price_levels {
+            let style = Style::default()
+                .fg(level.color)
+                .bg(self.theme.background)
+                .add_modifier(Modifier::REVERSED);
+            let (row, label) = match y_axis.calc_row(level.price) {
+                Some(row) => {
+                    let value = format!(
+                        "{:>width$}",
+                        y_axis.format(level.price),
+                        width = value_width as usize
+                    );
+                    if self.y_axis_placement.has_left() {
+                        buf.set_string(area.left() - value_width - 3, area.y + row, &value, style);
+                    }
+                    if self.y_axis_placement.has_right() {
+                        buf.set_string(area.right() + 3, area.y + row, &value, style);
+                    }
+                    (row, level.label.clone())
+                }
+                None if level.show_offscreen => {
+                    let (row, arrow) = if level.price > y_axis.max() {
+                        (0, LEVEL_ABOVE)
+                    } else {
+                        (y_axis.height() - 1, LEVEL_BELOW)
+                    };
+                    let value = y_axis.format(level.price);
+                    (row, format!("{} {} {}", arrow, level.label, value.trim()))
+                }
+                None => continue,
+            };
+
+            let label = format!(" {} ", label.split_whitespace().join(" "));
+            let label_width = label.chars().count() as u16;
+            if label.trim().is_empty() || label_width > area.width {
+                continue;
+            }
+            let x = if self.y_axis_placement.has_left() {
+                area.left()
+            } else {
+                area.right() - label_width
+            };
+            buf.set_string(x, area.y + row, label, style);
+        }
+    }
+
+    /// draw markers on their candles, stacking the ones sharing a column away from the candle
+    fn render_markers(
+        &self,
+        buf: &mut Buffer,
+        area: Rect,
+        y_axis: &YAxis,
+        columns: &[(u16, &Candle)],
+    ) -> Vec<((u16, u16), Marker)> {
+        let interval = self.interval as i64 * 1000;
+        let mut occupied = HashSet::new();
+        let mut rendered = Vec::new();
+        for marker in &self.markers {
+            let Some((column, candle)) = columns.iter().find(|(_, c)| {
+                c.timestamp <= marker.timestamp && marker.timestamp < c.timestamp + interval
+            }) else {
+                continue;
+            };
+
+            let row = match (marker.price, marker.side) {
+                (Some(price), _) => y_axis.calc_row(price),
+                (None, MarkerSide::Above) => y_axis
+                    .calc_row(candle.high)
+                    .and_then(|row| row.checked_sub(1)),
+                (None, MarkerSide::Below) => y_axis.calc_row(candle.low).map(|row| row + 1),
+            };
+            let Some(mut row) = row else {
+                continue;
+            };
+            while occupied.contains(&(*column, row)) {
+                row = match marker.side {
+                    MarkerSide::Above => match row.checked_sub(1) {
+                        Some(row) => row,
+                        None => break,
+                    },
+                    MarkerSide::Below => row + 1,
+                };
+            }
+            if row >= y_axis.height() || occupied.contains(&(*column, row)) {
+                continue;
+            }
+
+            occupied.insert((*column, row));
+            let position = (area.x + column, area.y + row);
+            buf.get_mut(position.0, position.1)
+                .set_symbol(&marker.glyph)
+                .set_style(
+                    Style::default()
+                        .fg(marker.color_in(&self.theme))
+                        .bg(self.theme.background),
+                );
+            rendered.push((position, marker.clone()));
+        }
+
+        rendered
+    }
+
+    /// shade the range between the anchor and the cursor, and draw the info box over it
+    fn render_measurement(
+        &self,
+        buf: &mut Buffer,
+        projection: &Projection,
+        candles: &[Candle],
+        anchor: i64,
+        cursor: i64,
+    ) -> Option<Measurement> {
+        // candles at or before the timestamps
+        let candle_at = |timestamp| {
+            let idx = candles.partition_point(|c| c.timestamp <= timestamp);
+            idx.checked_sub(1).map(|idx| &candles[idx])
+        };
+        let start = candle_at(anchor.min(cursor))?;
+        let end = candle_at(anchor.max(cursor))?;
+        let measurement = Measurement::new(start, end, self.interval as i64 * 1000);
+
+        let area = projection.area;
+        let left = projection.column(start.timestamp).max(0);
+        let right = projection.column(end.timestamp).min(area.width as i64 - 1);
+        if left <= right {
+            let span = Rect::new(
+                area.x + left as u16,
+                area.y,
+                (right - left + 1) as u16,
+                area.height,
+            );
+            buf.set_style(span, Style::default().bg(self.theme.selection));
+        }
+
+        let lines = measurement.render(&self.numeric, self.display_timezone);
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 2;
+        if width <= area.width && lines.len() as u16 <= area.height {
+            let x = area.x + (left.max(0) as u16).min(area.width - width);
+            let style = Style::default()
+                .fg(self.theme.label)
+                .bg(self.theme.selection)
+                .add_modifier(Modifier::BOLD);
+            for (y, line) in lines.iter().enumerate() {
+                buf.set_string(
+                    x,
+                    area.y + y as u16,
+                    format!(" {:<1$} ", line, width as usize - 2),
+                    style,
+                );
+            }
+        }
+
+        Some(measurement)
+    }
+}
+
+/// finite and ordered, with some height even for flat prices
+fn sanitize_y_range((min, max): (Float, Float)) -> (Float, Float) {
+    let finite = |value: Float| {
+        if value.is_nan() {
+            Float::from(0.)
+        } else {
+            value.clamp(Float::from(f64::MIN), Float::from(f64::MAX))
+        }
+    };
+    let (min, max) = (finite(min), finite(max));
+    let (min, max) = if min <= max { (min, max) } else { (max, min) };
+    if min == max {
+        let padding = if min == 0. { 1. } else { min.abs() * 0.01 };
+        (min - padding, max + padding)
+    } else {
+        (min, max)
+    }
 }
 
 impl Styled for CandleStickChart {
@@ -107,137 +643,241 @@ impl StatefulWidget for CandleStickChart {
     ///     |      x axis area      |
     ///     |-----------------------|
     ///
-    ///
+    /// y axis area is mirrored to the right side with `YAxisPlacement::Right`, or drawn on the
+    /// both sides with `YAxisPlacement::Both`.
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        if self.candles.is_empty() {
+        let area = area.intersection(buf.area);
+        buf.set_style(area, Style::default().bg(self.theme.background));
+        state.chart_area = None;
+
+        // candles which cannot be drawn are skipped rather than breaking the whole chart
+        let data = normalize_candles(
+            self.candles.iter().filter(|c| c.is_renderable()).cloned(),
+            self.interval,
+            self.duplicate_policy,
+            self.drop_unaligned,
+        );
+        let (Some(first), Some(last)) = (data.first(), data.last()) else {
+            let message = if state.is_loading_history() {
+                LOADING
+            } else {
+                &self.empty_message
+            };
+            self.render_centered(buf, area, message);
             return;
-        }
+        };
+        let first_timestamp = first.timestamp;
+        let last_timestamp = last.timestamp;
 
-        let global_min = self.candles.iter().map(|c| c.low).min().unwrap();
-        let global_max = self.candles.iter().map(|c| c.high).max().unwrap();
+        let global_min = data.iter().map(|c| c.low).min().unwrap_or_default();
+        let global_max = data.iter().map(|c| c.high).max().unwrap_or_default();
+        let (global_min, global_max) = match state.y_range {
+            Some((min, max)) => sanitize_y_range((global_min.min(min), global_max.max(max))),
+            None => sanitize_y_range(self.auto_fit.fit(&[global_min], &[global_max])),
+        };
 
-        let y_axis_width: u16 =
+        let mut y_axis_width: u16 =
             YAxis::estimated_width(self.numeric.clone(), global_min, global_max);
-        if area.width <= y_axis_width || area.height <= 3 {
+        if !self.comparisons.is_empty() && *global_min > 0. {
+            let ratio = self
+                .comparisons
+                .iter()
+                .filter_map(Comparison::ratio)
+                .fold(global_max / global_min, Float::max);
+            y_axis_width =
+                y_axis_width.max(YAxis::estimated_percent_width(self.numeric.clone(), ratio));
+        }
+        let left_width = if self.y_axis_placement.has_left() {
+            y_axis_width
+        } else {
+            0
+        };
+        let right_width = if self.y_axis_placement.has_right() {
+            y_axis_width
+        } else {
+            0
+        };
+        if area.width as u32 <= left_width as u32 + right_width as u32 || area.height <= 3 {
             return;
         }
 
-        let chart_width = area.width - y_axis_width;
-        let chart_width_usize = chart_width as usize;
-
-        // with first/last dummies
-        let first_timestamp = self.candles.first().unwrap().timestamp;
-        let last_timestamp = self.candles.last().unwrap().timestamp;
-
-        let mut candles = Vec::new();
-        for i in (1..=(chart_width as i64 - 1)).rev() {
-            candles.push(
-                Candle::new(
-                    first_timestamp - i * self.interval as i64 * 1000,
-                    0.,
-                    0.,
-                    0.,
-                    0.,
-                )
-                .unwrap(),
-            );
-        }
-        candles.extend(self.candles.clone());
-        for i in 1..=(chart_width as i64 - 1) {
-            candles.push(
-                Candle::new(
-                    last_timestamp + i * self.interval as i64 * 1000,
-                    0.,
-                    0.,
-                    0.,
-                    0.,
-                )
-                .unwrap(),
-            );
-        }
+        let chart_x = area.x + left_width;
+        let chart_width = area.width - left_width - right_width;
+        let interval = self.interval.as_millis();
 
-        let chart_end_timestamp = state.cursor_timestamp.unwrap_or(last_timestamp);
-        let chart_start_timestamp =
-            chart_end_timestamp - self.interval as i64 * 1000 * (chart_width_usize as i64 - 1);
-        let rendered_candles = candles
+        // the cursor can scroll until only the first candle is left at the right edge, or until
+        // the last candle is left at the left edge
+        let cursor_first_timestamp = first_timestamp;
+        let cursor_last_timestamp = last_timestamp + interval * (chart_width as i64 - 1);
+        let chart_end_timestamp = state
+            .cursor_timestamp
+            .unwrap_or(last_timestamp)
+            .clamp(cursor_first_timestamp, cursor_last_timestamp);
+        let chart_start_timestamp = chart_end_timestamp - interval * (chart_width as i64 - 1);
+        let rendered_candles = data
             .iter()
             .filter(|c| c.timestamp >= chart_start_timestamp && c.timestamp <= chart_end_timestamp)
             .collect_vec();
 
+        // comparisons are scaled to the price of the first visible candle
+        let visible_range = (chart_start_timestamp, chart_end_timestamp);
+        let percent_base = rendered_candles
+            .first()
+            .filter(|c| !self.comparisons.is_empty() && *c.close > 0.);
+        let comparisons = percent_base.map_or_else(Vec::new, |first| {
+            self.comparisons
+                .iter()
+                .filter_map(|c| c.scale(self.interval, visible_range, first.timestamp, first.close))
+                .collect_vec()
+        });
+
+        let (y_min, y_max) = match state.y_range {
+            Some(y_range) => sanitize_y_range(y_range),
+            None => {
+                let (mut lows, mut highs): (Vec<_>, Vec<_>) =
+                    rendered_candles.iter().map(|c| (c.low, c.high)).unzip();
+                for price in comparisons.iter().flat_map(|c| c.visible(visible_range)) {
+                    lows.push(price);
+                    highs.push(price);
+                }
+                sanitize_y_range(self.auto_fit.fit(&lows, &highs))
+            }
+        };
+
         state.set_info(CandleStikcChartInfo::new(
-            candles[chart_width_usize - 1].timestamp,
-            candles.last().unwrap().timestamp,
+            cursor_first_timestamp,
+            cursor_last_timestamp,
             self.interval,
             last_timestamp,
-            rendered_candles.first().unwrap().timestamp < first_timestamp,
+            visible_range,
+            rendered_candles.len(),
+            (y_min, y_max),
         ));
 
-        let y_min = rendered_candles
-            .iter()
-            .filter(|c| c.timestamp >= first_timestamp && c.timestamp <= last_timestamp)
-            .map(|c| c.low)
-            .min()
-            .unwrap();
-        let y_max = rendered_candles
-            .iter()
-            .filter(|c| c.timestamp >= first_timestamp && c.timestamp <= last_timestamp)
-            .map(|c| c.high)
-            .max()
-            .unwrap();
-
-        let y_axis = YAxis::new(Numeric::default(), area.height - 3, y_min, y_max);
-        let rendered_y_axis = y_axis.render();
-        for (y, string) in rendered_y_axis.iter().enumerate() {
-            buf.set_string(0, y as u16, string, Style::default());
+        let mut y_axis = YAxis::new(self.numeric.clone(), area.height - 3, y_min, y_max);
+        if let Some(first) = percent_base {
+            y_axis = y_axis.percent_of(first.close);
+        }
+        if self.y_axis_placement.has_left() {
+            for (y, string) in y_axis.render(Side::Left).iter().enumerate() {
+                let x = chart_x as i32 - string.chars().count() as i32;
+                self.set_axis_string(buf, area, x, area.y + y as u16, string);
+            }
+        }
+        if self.y_axis_placement.has_right() {
+            for (y, string) in y_axis.render(Side::Right).iter().enumerate() {
+                let x = (chart_x + chart_width) as i32;
+                self.set_axis_string(buf, area, x, area.y + y as u16, string);
+            }
         }
 
-        let timestamp_min = rendered_candles.first().unwrap().timestamp;
-        let timestamp_max = rendered_candles.last().unwrap().timestamp;
+        let timestamp_min = chart_start_timestamp;
+        let timestamp_max = chart_end_timestamp;
+
+        // following the latest candle which has not closed yet
+        let now = self.now.unwrap_or_else(|| SystemClock.now());
+        let is_realtime =
+            state.cursor_timestamp.is_none() && now < last_timestamp.saturating_add(interval);
 
         let x_axis = XAxis::new(
             chart_width,
             timestamp_min,
             timestamp_max,
             self.interval,
-            state.cursor_timestamp.is_none(),
-        );
+            is_realtime,
+        )
+        .now(now);
         let rendered_x_axis = x_axis.render(self.display_timezone);
-        buf.set_string(y_axis_width - 2, area.height - 3, "└──", Style::default());
+        let x_axis_y = area.bottom() - 3;
         for (y, string) in rendered_x_axis.iter().enumerate() {
-            buf.set_string(
-                y_axis_width,
-                area.height - 3 + y as u16,
-                string,
-                Style::default(),
-            );
+            self.set_axis_string(buf, area, chart_x as i32, x_axis_y + y as u16, string);
+        }
+        if self.y_axis_placement.has_left() {
+            self.set_axis_string(buf, area, chart_x as i32 - 2, x_axis_y, "└─");
+        }
+        if self.y_axis_placement.has_right() {
+            let x = (chart_x + chart_width) as i32;
+            self.set_axis_string(buf, area, x, x_axis_y, "─┘");
+        }
+
+        let chart_area = Rect::new(chart_x, area.y, chart_width, y_axis.height());
+        if self.grid {
+            let x_ticks = rendered_x_axis[0]
+                .chars()
+                .positions(|c| c == AXIS_TICK)
+                .filter(|&column| column < chart_width as usize)
+                .collect_vec();
+            self.render_grid(buf, chart_area, &y_axis, &x_ticks);
+        }
+        self.render_price_lines(buf, chart_area, &y_axis);
+
+        let projection = Projection {
+            area: chart_area,
+            start_timestamp: timestamp_min,
+            interval: self.interval,
+            y_axis: &y_axis,
+        };
+        state.chart_area = Some(chart_area);
+        if let Some(crosshair) = state.crosshair {
+            self.render_crosshair(buf, &projection, crosshair, y_axis_width - 4);
         }
 
-        let mut offset = 0;
-        let mut prev_timestamp =
-            rendered_candles.first().unwrap().timestamp - self.interval as i64 * 1000;
-        for (x, candle) in rendered_candles.iter().enumerate() {
-            if candle.timestamp < first_timestamp || candle.timestamp > last_timestamp {
-                prev_timestamp = candle.timestamp;
+        // chart column of each drawn candle, candles sharing a column are drawn over each other
+        let mut columns = Vec::new();
+        for candle in rendered_candles {
+            let column = projection.column(candle.timestamp);
+            if !(0..chart_width as i64).contains(&column) {
                 continue;
             }
-            let gap = (candle.timestamp - prev_timestamp) / (self.interval as i64 * 1000);
-            if gap > 1 {
-                offset += gap as u16 - 1;
-            }
+            let column = column as u16;
+            columns.push((column, candle));
             let (candle_type, rendered) = candle.render(&y_axis);
 
             let color = match candle_type {
-                CandleType::Bearish => self.bearish_color,
-                CandleType::Bullish => self.bullish_color,
+                CandleType::Bearish => self.theme.bearish,
+                CandleType::Bullish => self.theme.bullish,
             };
 
             for (y, char) in rendered.iter().enumerate() {
-                buf.get_mut(x as u16 + y_axis_width + offset, y as u16)
-                    .set_symbol(char)
-                    .set_style(Style::default().fg(color));
+                let cell = buf.get_mut(chart_x + column, area.y + y as u16);
+                if *char == UNICODE_VOID && BACKGROUND_SYMBOLS.contains(&cell.symbol()) {
+                    continue;
+                }
+                cell.set_symbol(char)
+                    .set_style(Style::default().fg(color).bg(self.theme.background));
+            }
+            if candle.high > y_max && candle.low <= y_max {
+                buf.get_mut(chart_x + column, area.y)
+                    .set_symbol(UNICODE_CLIPPED_UP);
+            }
+            if candle.low < y_min && candle.high >= y_min {
+                buf.get_mut(chart_x + column, area.y + y_axis.height() - 1)
+                    .set_symbol(UNICODE_CLIPPED_DOWN);
+            }
+        }
+
+        self.render_gaps(buf, chart_area, &columns);
+        self.render_comparisons(buf, &projection, &comparisons);
+
+        for drawing in &state.drawings {
+            let style = Style::default().fg(drawing.color).bg(self.theme.background);
+            for ((x, y), symbol) in drawing.render(&projection) {
+                buf.get_mut(x, y).set_symbol(&symbol).set_style(style);
             }
-            prev_timestamp = candle.timestamp;
         }
+
+        self.render_price_labels(buf, chart_area, &y_axis, y_axis_width - 4);
+        self.render_legend(buf, chart_area, &comparisons);
+        if state.is_loading_history() {
+            let columns_before_first = projection
+                .column(first_timestamp)
+                .clamp(0, chart_width as i64);
+            self.render_loading(buf, chart_area, columns_before_first as u16);
+        }
+        state.markers = self.render_markers(buf, chart_area, &y_axis, &columns);
+        state.measurement = state.measure_anchor.and_then(|anchor| {
+            self.render_measurement(buf, &projection, &data, anchor, chart_end_timestamp)
+        });
     }
 }
 
@@ -247,18 +887,42 @@ mod tests {
         assert_buffer_eq,
         buffer::{Buffer, Cell},
         layout::Rect,
-        style::{Style, Stylize},
+        style::{Color, Style, Stylize},
         widgets::StatefulWidget,
     };
 
-    use crate::{Candle, CandleStickChart, CandleStickChartState, Interval};
+    use crate::{
+        Anchor, AutoFit, Candle, CandleStickChart, CandleStickChartState, Comparison, DrawingKind,
+        DuplicatePolicy, Interval, LineStyle, Marker, MarkerSide, PriceLevel, Theme,
+        YAxisPlacement,
+    };
 
     fn render(widget: CandleStickChart, width: u16, height: u16) -> Buffer {
+        render_with_state(widget, width, height, &mut CandleStickChartState::default())
+    }
+
+    fn render_with_state(
+        widget: CandleStickChart,
+        width: u16,
+        height: u16,
+        state: &mut CandleStickChartState,
+    ) -> Buffer {
+        // a time after the candles unless the test sets it
+        let widget = match widget.now {
+            Some(_) => widget,
+            None => {
+                let last = widget.candles.iter().map(|c| c.timestamp).max();
+                let now = last
+                    .unwrap_or(0)
+                    .saturating_add(widget.interval.as_millis() - 1);
+                widget.now(now)
+            }
+        };
         let area = Rect::new(0, 0, width, height);
         let mut cell = Cell::default();
         cell.set_symbol("x");
         let mut buffer = Buffer::filled(area, &cell);
-        widget.render(area, &mut buffer, &mut CandleStickChartState::default());
+        widget.render(area, &mut buffer, state);
         buffer.set_style(area, Style::default().reset());
         buffer
     }
@@ -266,7 +930,7 @@ mod tests {
     #[test]
     fn empty_candle() {
         let widget = CandleStickChart::new(Interval::OneMinute).candles(vec![]);
-        let buffer = render(widget, 14, 8);
+        let buffer = render(widget.clone(), 14, 8);
         assert_buffer_eq!(
             buffer,
             Buffer::with_lines(vec![
@@ -274,10 +938,63 @@ mod tests {
                 "xxxxxxxxxxxxxx",
                 "xxxxxxxxxxxxxx",
                 "xxxxxxxxxxxxxx",
+                "xxxno dataxxxx",
                 "xxxxxxxxxxxxxx",
                 "xxxxxxxxxxxxxx",
                 "xxxxxxxxxxxxxx",
-                "xxxxxxxxxxxxxx",
+            ])
+        );
+
+        let buffer = render(widget.empty_message(""), 14, 8);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::filled(buffer.area, Cell::default().set_symbol("x"))
+        );
+    }
+
+    #[test]
+    fn gaps() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+                Candle::new(180000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+            ])
+            .gap_symbol("░");
+        let buffer = render(widget, 17, 8);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     4.200 ├  ░░╽",
+                "           │ │░░┃",
+                "           │ │░░╹",
+                "           │ │░░ ",
+                "     0.840 ├ │░░ ",
+                "xxxxxxxxxxx└─────",
+                "xxxxxxxxxxxxx    ",
+                "xxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn loading_before_first_candle() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![Candle::new(600000, 0.9, 3.0, 0.0, 2.1).unwrap()]);
+        let mut state = CandleStickChartState::default();
+        render_with_state(widget.clone(), 30, 8, &mut state);
+        state.next_history_request().unwrap();
+        let buffer = render_with_state(widget, 30, 8, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     3.000 ├ xxxxxxxxxxxxxxxx│",
+                "           │ xxxxxxxxxxxxxxxx│",
+                "           │ xxxxloading…xxxx┃",
+                "           │ xxxxxxxxxxxxxxxx│",
+                "     0.600 ├ xxxxxxxxxxxxxxxx│",
+                "xxxxxxxxxxx└───────┴─────────┴",
+                "xxxxxxxxxxxxx    1970   *00:10",
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
             ])
         );
     }
@@ -437,4 +1154,821 @@ mod tests {
             ])
         );
     }
+
+    #[test]
+    fn themed_candle() {
+        let theme = Theme::colorblind();
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap()])
+            .theme(theme);
+        let area = Rect::new(0, 0, 14, 8);
+        let mut buffer = Buffer::empty(area);
+        widget.render(area, &mut buffer, &mut CandleStickChartState::default());
+
+        assert_eq!(buffer.get(13, 2).symbol(), "┃");
+        assert_eq!(buffer.get(13, 2).fg, theme.bullish);
+        assert_eq!(buffer.get(11, 0).symbol(), "├");
+        assert_eq!(buffer.get(11, 0).fg, theme.axis);
+        assert_eq!(buffer.get(5, 0).fg, theme.label);
+    }
+
+    #[test]
+    fn themed_markers() {
+        let theme = Theme::light();
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 2., 4., 0., 3.).unwrap(),
+                Candle::new(60000, 2., 3., 1., 2.5).unwrap(),
+            ])
+            .markers(vec![
+                Marker::buy(60000),
+                Marker::sell(60000),
+                Marker::sell(0).price(2.).color(Color::Blue),
+            ])
+            .theme(theme);
+        let area = Rect::new(0, 0, 15, 10);
+        let mut buffer = Buffer::empty(area);
+        widget.render(area, &mut buffer, &mut CandleStickChartState::default());
+
+        let colors = |glyph| {
+            buffer
+                .content
+                .iter()
+                .filter(|cell| cell.symbol() == glyph)
+                .map(|cell| cell.fg)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(colors("▲"), vec![theme.bullish]);
+        assert_eq!(colors("▼"), vec![theme.bearish, Color::Blue]);
+    }
+
+    #[test]
+    fn grid_beneath_candles() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+                Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+                Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+            ])
+            .grid(true);
+        let buffer = render(widget, 30, 8);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     4.200 ├ ┈┈┈┈┈┈┈┈┈┈┈┈┈┈┈╽┃",
+                "           │ xxxxxxxxxxxxxx│┃┃",
+                "           │ xxxxxxxxxxxxxx│╹╿",
+                "           │ xxxxxxxxxxxxxx│ ┊",
+                "     0.840 ├ ┈┈┈┈┈┈┈┈┈┈┈┈┈┈│┈┼",
+                "xxxxxxxxxxx└─────────────────┴",
+                "xxxxxxxxxxxxx           *00:02",
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn right_y_axis() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+                Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+                Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+            ])
+            .y_axis_placement(YAxisPlacement::Right);
+        let buffer = render(widget, 19, 8);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "xxx ╽┃ ┤     4.200 ",
+                "xxx│┃┃ │           ",
+                "xxx│╹╿ │           ",
+                "xxx│   │           ",
+                "xxx│   ┤     0.840 ",
+                "─────┴─┘xxxxxxxxxxx",
+                "*00:02xxxxxxxxxxxxx",
+                "xxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn both_y_axis() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+                Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+                Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+            ])
+            .y_axis_placement(YAxisPlacement::Both);
+        let buffer = render(widget, 30, 8);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     4.200 ├ x ╽┃ ┤     4.200 ",
+                "           │ x│┃┃ │           ",
+                "           │ x│╹╿ │           ",
+                "           │ x│   │           ",
+                "     0.840 ├ x│   ┤     0.840 ",
+                "xxxxxxxxxxx└──────┘xxxxxxxxxxx",
+                "xxxxxxxxxxxxx    xxxxxxxxxxxxx",
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn locked_y_range() {
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(vec![
+            Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+            Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+            Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+        ]);
+        let mut state = CandleStickChartState::default();
+        state.lock_y_range(2.0, 4.0);
+        let buffer = render_with_state(widget, 19, 8, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     4.000 ├ xxx ↑↑",
+                "           │ xxx ┃┃",
+                "           │ xxx╷┃┃",
+                "           │ xxx│┃┃",
+                "     2.400 ├ xxx↓┃╿",
+                "xxxxxxxxxxx└──────┴",
+                "xxxxxxxxxxxxx*00:02",
+                "xxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn pan_and_zoom_y_range() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap()]);
+        let mut state = CandleStickChartState::default();
+        render_with_state(widget, 14, 8, &mut state);
+        assert_eq!(state.locked_y_range(), None);
+
+        state.try_move_up();
+        assert_eq!(state.locked_y_range(), Some((0.375, 3.375)));
+        state.zoom_y_out();
+        assert_eq!(state.locked_y_range(), Some((0.0, 3.75)));
+        state.reset_y_range();
+        assert_eq!(state.locked_y_range(), None);
+    }
+
+    #[test]
+    fn price_levels() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+                Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+                Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+            ])
+            .price_levels(vec![
+                PriceLevel::new(1.0).line_style(LineStyle::Dashed),
+                PriceLevel::new(3.0).label("TP"),
+                PriceLevel::new(9.0).label("SL").show_offscreen(true),
+                PriceLevel::new(-1.0).label("hidden"),
+            ]);
+        let buffer = render(widget.clone(), 30, 8);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     4.200 ├  ↑ SL 9.000 xx ╽┃",
+                "     3.000 │  TP ──────────│┃┃",
+                "           │ xxxxxxxxxxxxxx│╹╿",
+                "     1.000 │ ╌╌╌╌╌╌╌╌╌╌╌╌╌╌│╌╌",
+                "     0.840 ├ xxxxxxxxxxxxxx│  ",
+                "xxxxxxxxxxx└─────────────────┴",
+                "xxxxxxxxxxxxx           *00:02",
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+
+        // off-screen values are labeled like the axis, here in percent
+        let widget = widget
+            .price_levels(vec![PriceLevel::new(-1.0).label("SL").show_offscreen(true)])
+            .comparisons(vec![Comparison::new("ETH", [(0, 1.)])]);
+        assert_buffer_eq!(
+            render(widget, 30, 8),
+            Buffer::with_lines(vec![
+                "  100.000% ├ ━ ETH +0.00%xx ╽┃",
+                "           │ xxxxxxxxxxxxxx│┃┃",
+                "           │ xxxxxxxxxxxxxx•╹╿",
+                "           │ xxxxxxxxxxxxxx│  ",
+                "  -60.000% ├  ↓ SL -147.619%  ",
+                "xxxxxxxxxxx└─────────────────┴",
+                "xxxxxxxxxxxxx           *00:02",
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn markers() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+                Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+                Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+                Candle::new(180000, 2.3, 2.5, 1.3, 2.0).unwrap(),
+            ])
+            .markers(vec![
+                Marker::buy(3000),
+                Marker::buy(12000).glyph("B"),
+                Marker::sell(180000),
+                Marker::sell(181000).glyph("S"),
+                Marker::new(120000, MarkerSide::Above).price(2.0).glyph("*"),
+                Marker::new(999999, MarkerSide::Above),
+            ])
+            .auto_fit(AutoFit::default().padding(0.5));
+        let mut state = CandleStickChartState::default();
+        let buffer = render_with_state(widget, 19, 11, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     6.300 ├ xx    ",
+                "           │ xx ╷ S",
+                "           │ xx ╽┃▼",
+                "           │ xx│┃╿╻",
+                "     2.100 ├ xx┃ *│",
+                "           │ xx│   ",
+                "           │ xx▲   ",
+                "           │ xxB   ",
+                "xxxxxxxxxxx└──────┴",
+                "xxxxxxxxxxxxx*00:03",
+                "xxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+        assert_eq!(state.marker_at(18, 2), Some(&Marker::sell(180000)));
+        assert_eq!(
+            state.marker_at(18, 1),
+            Some(&Marker::sell(181000).glyph("S"))
+        );
+        assert_eq!(state.marker_at(17, 1), None);
+        assert_eq!(state.rendered_markers().count(), 5);
+    }
+
+    #[test]
+    fn drawings() {
+        let candles = (0..12)
+            .map(|i| {
+                let price = i as f64;
+                Candle::new(i * 60000, price, price + 1., price, price + 1.).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let mut state = CandleStickChartState::default();
+        let line = state.add_drawing(
+            DrawingKind::TrendLine,
+            Anchor::new(0, 12.),
+            Anchor::new(660000, 0.),
+            Color::Reset,
+        );
+        let rectangle = state.add_drawing(
+            DrawingKind::Rectangle,
+            Anchor::new(60000, 11.),
+            Anchor::new(240000, 7.),
+            Color::Reset,
+        );
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(candles.clone());
+        let buffer = render_with_state(widget, 25, 11, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "    12.000 ├  ┌──┐     ╻┃",
+                "           │  │╲╲│   ╻┃╹ ",
+                "           │  │  │  ╻┃   ",
+                "           │  └──┘╲┃╹    ",
+                "     6.000 ├     ╻┃╲╲    ",
+                "           │   ╻┃╹   ╲   ",
+                "           │  ╻┃      ╲╲ ",
+                "           │ ┃╹         ╲",
+                "xxxxxxxxxxx└────────────┴",
+                "xxxxxxxxxxxxx      *00:11",
+                "xxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+
+        assert!(state.move_drawing(rectangle, 360000, -6.));
+        assert!(state.remove_drawing(line).is_some());
+        assert!(!state.move_drawing(line, 0, 0.));
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(candles);
+        let buffer = render_with_state(widget, 25, 11, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "    12.000 ├           ╻┃",
+                "           │         ╻┃╹ ",
+                "           │        ╻┃   ",
+                "           │      ╻┃╹    ",
+                "     6.000 ├     ╻┃ ┌──┐ ",
+                "           │   ╻┃╹  │  │ ",
+                "           │  ╻┃    │  │ ",
+                "           │ ┃╹     └──┘ ",
+                "xxxxxxxxxxx└────────────┴",
+                "xxxxxxxxxxxxx      *00:11",
+                "xxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+
+        // the timestamps stop at the range which can be rendered
+        assert!(state.move_drawing(rectangle, i64::MAX, 0.));
+        assert!(state.move_drawing(rectangle, i64::MAX, 0.));
+        assert_eq!(state.drawings()[0].start.timestamp, 8_000_000_000_000_000);
+    }
+
+    #[test]
+    fn ray_and_fibonacci_drawings() {
+        let candles = (0..12)
+            .map(|i| Candle::new(i * 60000, 1., 12., 0., 11.).unwrap())
+            .collect::<Vec<_>>();
+        let mut state = CandleStickChartState::default();
+        // from the start anchor to the right edge, the end anchor is ignored
+        let ray = state.add_drawing(
+            DrawingKind::HorizontalRay,
+            Anchor::new(180000, 6.),
+            Anchor::new(240000, 6.),
+            Color::Reset,
+        );
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(candles.clone());
+        let buffer = render_with_state(widget, 25, 11, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "    12.000 ├ ╽╽╽╽╽╽╽╽╽╽╽╽",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ ┃┃┃─────────",
+                "     6.000 ├ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ ╿╿╿╿╿╿╿╿╿╿╿╿",
+                "xxxxxxxxxxx└────────────┴",
+                "xxxxxxxxxxxxx      *00:11",
+                "xxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+
+        assert!(state.remove_drawing(ray).is_some());
+        // all seven levels from the end back to the start
+        state.add_drawing(
+            DrawingKind::Fibonacci,
+            Anchor::new(0, 11.),
+            Anchor::new(660000, 1.),
+            Color::Reset,
+        );
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(candles);
+        let buffer = render_with_state(widget, 25, 18, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "    12.000 ├ ││││││││││││",
+                "           │ 1.000┄┄┄┄┄┄┄",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ 0.786┄┄┄┄┄┄┄",
+                "     8.800 ├ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ 0.618┄┄┄┄┄┄┄",
+                "           │ 0.500┄┄┄┄┄┄┄",
+                "     5.600 ├ 0.382┄┄┄┄┄┄┄",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ 0.236┄┄┄┄┄┄┄",
+                "           │ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "     2.400 ├ ┃┃┃┃┃┃┃┃┃┃┃┃",
+                "           │ 0.000┄┄┄┄┄┄┄",
+                "           │ ││││││││││││",
+                "xxxxxxxxxxx└────────────┴",
+                "xxxxxxxxxxxxx      *00:11",
+                "xxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn measure() {
+        let candles = (0..30)
+            .map(|i| {
+                let price = 100. + i as f64;
+                Candle::new(i * 60000, price, price + 1., price, price + 1.).unwrap()
+            })
+            .collect::<Vec<_>>();
+        let widget = || CandleStickChart::new(Interval::OneMinute).candles(candles.clone());
+        let mut state = CandleStickChartState::default();
+        render_with_state(widget(), 40, 8, &mut state);
+
+        state.try_move_backward();
+        state.start_measure();
+        for _ in 0..4 {
+            state.try_move_backward();
+        }
+        let buffer = render_with_state(widget(), 40, 8, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "   125.000 ├  +4.000 (+3.20%)           ",
+                "           │  4 bars, 4m                ",
+                "           │  01/01 00:24 → 01/01 00:28 ",
+                "           │ xx    ╻╻╻┃┃╹               ",
+                "   105.000 ├ xx╻╻┃┃╹                    ",
+                "xxxxxxxxxxx└───┴──────────────┴────────┴",
+                "xxxxxxxxxxxxx 1970          00:15  00:24",
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+
+        let measurement = state.measurement().unwrap();
+        assert_eq!(measurement.bars, 4);
+        assert_eq!(*measurement.delta, 4.);
+        assert_eq!(measurement.elapsed_millis(), 240000);
+
+        state.stop_measure();
+        assert!(!state.is_measuring());
+        assert_eq!(state.measurement(), None);
+    }
+
+    #[test]
+    fn unsorted_and_invalid_candles() {
+        let nan = Candle {
+            timestamp: 60000,
+            open: f64::NAN.into(),
+            high: f64::INFINITY.into(),
+            low: 0.into(),
+            close: 1.into(),
+        };
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(vec![
+            Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+            nan,
+            Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+            Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+        ]);
+        assert_eq!(
+            render(widget.clone(), 19, 8),
+            render(
+                widget.candles(vec![
+                    Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+                    Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+                    Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+                ]),
+                19,
+                8
+            )
+        );
+    }
+
+    #[test]
+    fn duplicate_and_unaligned_candles() {
+        let first = Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap();
+        let last = Candle::new(60000, 3.9, 4.0, 1.0, 1.5).unwrap();
+        let unaligned = Candle::new(90000, 1.0, 4.2, 0.0, 4.0).unwrap();
+        let base = vec![
+            Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+            Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+        ];
+        let with = |candles: &[&Candle]| {
+            let mut result = base.clone();
+            result.extend(candles.iter().map(|&c| c.clone()));
+            result
+        };
+        let widget = CandleStickChart::new(Interval::OneMinute).drop_unaligned(true);
+
+        assert_eq!(
+            render(
+                widget.clone().candles(with(&[&first, &last, &unaligned])),
+                19,
+                8
+            ),
+            render(widget.clone().candles(with(&[&last])), 19, 8)
+        );
+        assert_eq!(
+            render(
+                widget
+                    .clone()
+                    .duplicate_policy(DuplicatePolicy::KeepFirst)
+                    .candles(with(&[&first, &last])),
+                19,
+                8
+            ),
+            render(widget.clone().candles(with(&[&first])), 19, 8)
+        );
+        assert_eq!(
+            render(
+                widget
+                    .clone()
+                    .duplicate_policy(DuplicatePolicy::Merge)
+                    .candles(with(&[&first, &last])),
+                19,
+                8
+            ),
+            render(
+                widget.candles(with(&[&Candle::new(60000, 2.1, 4.2, 1.0, 1.5).unwrap()])),
+                19,
+                8
+            )
+        );
+    }
+
+    #[test]
+    fn realtime_marker_follows_clock() {
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(vec![
+            Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+            Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+        ]);
+        let has_marker = |widget: CandleStickChart, state: &mut CandleStickChartState| {
+            crate::buffer_to_string(&render_with_state(widget, 30, 8, state)).contains('*')
+        };
+        let mut state = CandleStickChartState::default();
+
+        // the last candle is open until 120000
+        assert!(has_marker(widget.clone().now(119999), &mut state));
+        assert!(!has_marker(widget.clone().clock(&|| 120000), &mut state));
+
+        state.try_move_backward();
+        assert!(!has_marker(widget.now(60000), &mut state));
+    }
+
+    #[test]
+    fn crosshair() {
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(vec![
+            Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap(),
+            Candle::new(60000, 2.1, 4.2, 2.1, 3.9).unwrap(),
+            Candle::new(120000, 3.9, 4.1, 2.0, 2.3).unwrap(),
+        ]);
+        let mut state = CandleStickChartState::default();
+        state.set_crosshair(Some(Anchor::new(60000, 1.)));
+        let buffer = render_with_state(widget, 20, 8, &mut state);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "     4.200 ├ xxxx ╽┃",
+                "           │ xxxx│┃┃",
+                "           │ xxxx│╹╿",
+                "     1.000 │ ╌╌╌╌│┼╌",
+                "     0.840 ├ xxxx│╎ ",
+                "xxxxxxxxxxx└───────┴",
+                "xxxxxxxxxxxxx *00:02",
+                "xxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn jump_to_timestamp() {
+        let widget = CandleStickChart::new(Interval::OneMinute).candles(
+            (0..100)
+                .map(|i| Candle::new(i * 60000, 1., 2., 0., 1.).unwrap())
+                .collect(),
+        );
+        let mut state = CandleStickChartState::default();
+        state.jump_to(0);
+        assert!(state.is_live());
+        assert_eq!(state.visible_range(), None);
+        assert_eq!(state.visible_candles(), 0);
+
+        // 17 columns
+        render_with_state(widget.clone(), 30, 10, &mut state);
+        assert_eq!(state.visible_range(), Some((4980000, 5940000)));
+        assert_eq!(state.visible_candles(), 17);
+
+        state.jump_to(1234567);
+        assert!(!state.is_live());
+        render_with_state(widget.clone(), 30, 10, &mut state);
+        assert_eq!(state.visible_range(), Some((240000, 1200000)));
+        assert_eq!(state.visible_candles(), 17);
+
+        state.jump_to(i64::MIN);
+        render_with_state(widget.clone(), 30, 10, &mut state);
+        assert_eq!(state.visible_range(), Some((-960000, 0)));
+        assert_eq!(state.visible_candles(), 1);
+
+        state.reset_cursor();
+        assert!(state.is_live());
+    }
+
+    #[test]
+    fn comparisons() {
+        let chart = |points: &[(i64, f64)]| {
+            CandleStickChart::new(Interval::OneMinute)
+                .candles(vec![
+                    Candle::new(0, 100., 110., 95., 100.).unwrap(),
+                    Candle::new(60000, 100., 120., 100., 115.).unwrap(),
+                    Candle::new(120000, 115., 118., 105., 110.).unwrap(),
+                    Candle::new(180000, 110., 112., 100., 105.).unwrap(),
+                ])
+                .comparisons(vec![Comparison::new("ETH", points.to_vec())])
+        };
+        // no price in the third minute
+        let points = [(0, 10.), (60000, 9.), (180000, 12.)];
+        let buffer = render(chart(&points), 30, 12);
+        assert_buffer_eq!(
+            buffer,
+            Buffer::with_lines(vec![
+                "   20.000% ├ ━ ETH +20.00% │╷•",
+                "           │ xxxxxxxxxxxxx ││ ",
+                "           │ xxxxxxxxxxxxx╷┃┃╽",
+                "           │ xxxxxxxxxxxxx│┃│┃",
+                "    6.667% ├ xxxxxxxxxxxxx│┃╵│",
+                "           │ xxxxxxxxxxxxx•┃ │",
+                "           │ xxxxxxxxxxxxx·   ",
+                "           │ xxxxxxxxxxxxx╵·  ",
+                "   -6.667% ├ xxxxxxxxxxxxx •  ",
+                "xxxxxxxxxxx└─────────────────┴",
+                "xxxxxxxxxxxxx           *00:03",
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx",
+            ])
+        );
+
+        // a timestamp out of range is skipped
+        let mut points = points.to_vec();
+        points.insert(0, (i64::MIN, 1.));
+        assert_buffer_eq!(render(chart(&points), 30, 12), buffer);
+    }
+
+    #[test]
+    fn auto_fit_percentile_few_candles() {
+        let median = AutoFit::default().percentile(0.5);
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![Candle::new(0, 1.5, 2.0, 1.0, 1.8).unwrap()])
+            .auto_fit(median);
+        assert_buffer_eq!(
+            render(widget, 14, 8),
+            Buffer::with_lines(vec![
+                "     2.000 ├ │",
+                "           │ ┃",
+                "           │ │",
+                "           │ │",
+                "     1.200 ├ │",
+                "xxxxxxxxxxx└──",
+                "xxxxxxxxxxxxx ",
+                "xxxxxxxxxxxxxx",
+            ])
+        );
+
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![
+                Candle::new(0, 1.5, 2.0, 1.0, 1.8).unwrap(),
+                Candle::new(60000, 1.8, 3.0, 1.6, 2.8).unwrap(),
+                Candle::new(120000, 2.8, 2.9, 2.0, 2.2).unwrap(),
+            ])
+            .auto_fit(median);
+        assert_buffer_eq!(
+            render(widget, 16, 8),
+            Buffer::with_lines(vec![
+                "     2.900 ├  ↑╽",
+                "           │  ┃┃",
+                "           │  ┃│",
+                "           │ │┃╵",
+                "     1.860 ├ ↓│ ",
+                "xxxxxxxxxxx└────",
+                "xxxxxxxxxxxxx   ",
+                "xxxxxxxxxxxxxxxx",
+            ])
+        );
+    }
+
+    #[test]
+    fn stale_cursor_and_tiny_area() {
+        let widget = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![Candle::new(0, 1., 1., 1., 1.).unwrap()]);
+        let mut state = CandleStickChartState {
+            cursor_timestamp: Some(i64::MIN),
+            y_range: Some((f64::NAN.into(), f64::NEG_INFINITY.into())),
+            ..Default::default()
+        };
+        for (width, height) in [(0, 0), (1, 1), (12, 4), (13, 4), (200, 3)] {
+            render_with_state(widget.clone(), width, height, &mut state);
+        }
+
+        // area partly outside of the buffer
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 20, 10));
+        widget.render(Rect::new(10, 5, 40, 40), &mut buffer, &mut state);
+    }
+
+    mod proptests {
+        use proptest::prelude::*;
+        use ratatui::{buffer::Buffer, layout::Rect, style::Color, widgets::StatefulWidget};
+
+        use crate::{
+            Anchor, AutoFit, Candle, CandleStickChart, CandleStickChartState, DrawingKind,
+            Interval, Marker, MarkerSide, PriceLevel, YAxisPlacement,
+        };
+
+        fn price() -> impl Strategy<Value = f64> {
+            prop_oneof![
+                8 => -1e6..1e6f64,
+                1 => any::<f64>(),
+                1 => Just(0.),
+            ]
+        }
+
+        fn timestamp() -> impl Strategy<Value = i64> {
+            prop_oneof![
+                8 => (0..200i64).prop_map(|i| 1703976900000 + i * 60000),
+                1 => -1e13 as i64..1e13 as i64,
+                1 => any::<i64>(),
+            ]
+        }
+
+        fn candle() -> impl Strategy<Value = Candle> {
+            (timestamp(), price(), price(), price(), price()).prop_map(
+                |(timestamp, open, high, low, close)| Candle {
+                    timestamp,
+                    open: open.into(),
+                    high: high.into(),
+                    low: low.into(),
+                    close: close.into(),
+                },
+            )
+        }
+
+        fn interval() -> impl Strategy<Value = Interval> {
+            (0..Interval::iter().count()).prop_map(|i| Interval::iter().nth(i).unwrap())
+        }
+
+        fn placement() -> impl Strategy<Value = YAxisPlacement> {
+            prop_oneof![
+                Just(YAxisPlacement::Left),
+                Just(YAxisPlacement::Right),
+                Just(YAxisPlacement::Both),
+            ]
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(256))]
+
+            #[test]
+            fn render_never_panics(
+                candles in prop::collection::vec(candle(), 0..50),
+                interval in interval(),
+                placement in placement(),
+                (x, y, width, height) in (0..30u16, 0..30u16, 0..150u16, 0..50u16),
+                cursor in prop::option::of(timestamp()),
+                y_range in prop::option::of((price(), price())),
+                percentile in prop::option::of(0.0..1.0f64),
+                level in price(),
+                marker in timestamp(),
+                anchors in (timestamp(), price(), timestamp(), price()),
+                drawing_delta in (any::<i64>(), price()),
+                actions in prop::collection::vec(0..9u8, 0..10),
+            ) {
+                let mut auto_fit = AutoFit::default().padding(0.1);
+                if let Some(percentile) = percentile {
+                    auto_fit = auto_fit.percentile(percentile);
+                }
+                let chart = CandleStickChart::new(interval)
+                    .candles(candles)
+                    .y_axis_placement(placement)
+                    .auto_fit(auto_fit)
+                    .grid(true)
+                    .price_levels(vec![PriceLevel::new(level).label("level").show_offscreen(true)])
+                    .markers(vec![
+                        Marker::buy(marker),
+                        Marker::new(marker, MarkerSide::Above).price(level),
+                    ]);
+
+                let mut state = CandleStickChartState {
+                    cursor_timestamp: cursor,
+                    y_range: y_range.map(|(min, max)| (min.into(), max.into())),
+                    ..Default::default()
+                };
+                for kind in [
+                    DrawingKind::TrendLine,
+                    DrawingKind::HorizontalRay,
+                    DrawingKind::Rectangle,
+                    DrawingKind::Fibonacci,
+                ] {
+                    state.add_drawing(
+                        kind,
+                        Anchor::new(anchors.0, anchors.1),
+                        Anchor::new(anchors.2, anchors.3),
+                        Color::Red,
+                    );
+                }
+                let drawings = state.drawings().iter().map(|d| d.id).collect::<Vec<_>>();
+
+                let mut buffer = Buffer::empty(Rect::new(0, 0, 100, 40));
+                let area = Rect::new(x, y, width, height);
+                chart.clone().render(area, &mut buffer, &mut state);
+                for action in actions {
+                    match action {
+                        0 => state.try_move_backward(),
+                        1 => state.try_move_forward(),
+                        2 => state.try_move_up(),
+                        3 => state.zoom_y_in(),
+                        4 => state.zoom_y_out(),
+                        5 => state.start_measure(),
+                        6 => state.reset_cursor(),
+                        7 => {
+                            for id in &drawings {
+                                state.move_drawing(*id, drawing_delta.0, drawing_delta.1);
+                            }
+                        }
+                        _ => state.reset_y_range(),
+                    }
+                    chart.clone().render(area, &mut buffer, &mut state);
+                }
+            }
+        }
+    }
 }
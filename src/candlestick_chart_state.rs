@@ -1,6 +1,22 @@
 use std::cmp::{max, min};
 
-use crate::Interval;
+use ordered_float::OrderedFloat;
+use ratatui::{layout::Rect, style::Color};
+
+#[cfg(feature = "async")]
+use crate::AsyncCandleSource;
+use crate::{
+    candle::{MAX_TIMESTAMP, MIN_TIMESTAMP},
+    history::History,
+    Action, Anchor, Candle, CandleSource, ChartEvent, Drawing, DrawingId, DrawingKind, Float,
+    HistoryRequest, HistoryRequestId, Interval, IntoChartEvent, Keymap, Marker, Measurement,
+    MouseInput, MouseKind,
+};
+
+/// ratio of the visible y range moved by `try_move_up`/`try_move_down`
+const Y_PAN_RATIO: f64 = 0.125;
+/// ratio of the visible y range scaled by `zoom_y_in`/`zoom_y_out`
+const Y_ZOOM_RATIO: f64 = 1.25;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct CandleStikcChartInfo {
@@ -8,7 +24,12 @@ pub(crate) struct CandleStikcChartInfo {
     cursor_last_timestamp: i64,
     interval: Interval,
     latest_timestamp: i64,
-    need_previous_candles: bool,
+    /// timestamps of the first and the last column
+    visible_range: (i64, i64),
+    /// number of candles within the visible range
+    visible_candles: usize,
+    y_min: Float,
+    y_max: Float,
 }
 
 impl CandleStikcChartInfo {
@@ -17,22 +38,68 @@ impl CandleStikcChartInfo {
         cursor_last_timestamp: i64,
         interval: Interval,
         latest_timestamp: i64,
-        need_previous_candles: bool,
+        visible_range: (i64, i64),
+        visible_candles: usize,
+        (y_min, y_max): (Float, Float),
     ) -> Self {
         Self {
             cursor_first_timestamp,
             cursor_last_timestamp,
             latest_timestamp,
             interval,
-            need_previous_candles,
+            visible_range,
+            visible_candles,
+            y_min,
+            y_max,
         }
     }
+
+    /// number of columns of the visible range
+    fn visible_bars(&self) -> i64 {
+        let (start, end) = self.visible_range;
+        (end - start) / self.interval.as_millis() + 1
+    }
 }
 
+/// Only the viewport, the drawings and the measuring are serialized, everything derived from the
+/// last render is rebuilt by the next one
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct CandleStickChartState {
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) info: Option<CandleStikcChartInfo>,
+    /// `None` follows the latest candle
+    #[cfg_attr(feature = "serde", serde(rename = "cursor"))]
     pub(crate) cursor_timestamp: Option<i64>,
+    /// locked y range, auto-fitted to the visible candles if `None`
+    pub(crate) y_range: Option<(Float, Float)>,
+    /// markers drawn by the last render with their buffer positions
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) markers: Vec<((u16, u16), Marker)>,
+    /// trendlines, rays, rectangles and retracements anchored to the data
+    pub(crate) drawings: Vec<Drawing>,
+    pub(crate) next_drawing_id: DrawingId,
+    /// timestamp of the candle where the measuring started
+    pub(crate) measure_anchor: Option<i64>,
+    /// measurement between the anchor and the cursor by the last render
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) measurement: Option<Measurement>,
+    /// paging of older candles
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) history: History,
+    /// crosshair lines crossing at the anchor
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) crosshair: Option<Anchor>,
+    /// candle area of the last render, to map mouse positions to anchors
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) chart_area: Option<Rect>,
+    /// last position of the left button drag
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) drag_from: Option<(u16, u16)>,
 }
 
 impl CandleStickChartState {
@@ -73,15 +140,489 @@ impl CandleStickChartState {
         }
     }
 
+    /// the visible width to older candles
+    pub fn page_backward(&mut self) {
+        if let Some(info) = &self.info {
+            self.move_bars(-info.visible_bars());
+        }
+    }
+
+    /// the visible width to newer candles
+    pub fn page_forward(&mut self) {
+        if let Some(info) = &self.info {
+            self.move_bars(info.visible_bars());
+        }
+    }
+
+    /// scroll until the oldest candle is at the left edge
+    pub fn move_to_start(&mut self) {
+        if let Some(info) = &self.info {
+            let cursor =
+                info.cursor_first_timestamp + info.interval.as_millis() * (info.visible_bars() - 1);
+            self.cursor_timestamp = Some(min(cursor, info.cursor_last_timestamp));
+        }
+    }
+
+    /// scroll until the candle containing the timestamp is at the right edge, no-op before the
+    /// first render
+    pub fn jump_to(&mut self, timestamp: i64) {
+        if let Some(info) = &self.info {
+            let timestamp =
+                timestamp.clamp(info.cursor_first_timestamp, info.cursor_last_timestamp);
+            self.cursor_timestamp = Some(info.interval.align(timestamp));
+        }
+    }
+
+    /// timestamps of the first and the last column by the last render
+    pub fn visible_range(&self) -> Option<(i64, i64)> {
+        self.info.as_ref().map(|info| info.visible_range)
+    }
+
+    /// number of candles drawn by the last render
+    pub fn visible_candles(&self) -> usize {
+        self.info.as_ref().map_or(0, |info| info.visible_candles)
+    }
+
+    /// interval of the last render
+    pub(crate) fn interval(&self) -> Option<Interval> {
+        self.info.as_ref().map(|info| info.interval)
+    }
+
+    /// move the cursor by bars, to older candles if negative
+    fn move_bars(&mut self, bars: i64) {
+        if let Some(info) = &self.info {
+            let cursor = self
+                .cursor_timestamp
+                .unwrap_or(info.latest_timestamp)
+                .saturating_add(bars.saturating_mul(info.interval.as_millis()));
+            self.cursor_timestamp =
+                Some(cursor.clamp(info.cursor_first_timestamp, info.cursor_last_timestamp));
+        }
+    }
+
     pub fn is_needed_previous_candles(&self) -> bool {
         if let Some(info) = &self.info {
-            info.need_previous_candles
+            info.visible_range.0 < info.cursor_first_timestamp
         } else {
             false
         }
     }
 
+    /// range before the oldest candle to load once the viewport is within a screen of it, `None`
+    /// while a request is in flight or the range was already loaded
+    pub fn next_history_request(&mut self) -> Option<HistoryRequest> {
+        let info = self.info.as_ref()?;
+        let (start, end) = info.visible_range;
+        let prefetch_start = start - (end - start) - info.interval.as_millis();
+        if prefetch_start >= info.cursor_first_timestamp {
+            return None;
+        }
+
+        self.history
+            .request(info.interval, prefetch_start, info.cursor_first_timestamp)
+    }
+
+    /// the request is done and its range is not requested again, no received candles stop the
+    /// paging until `reset_history`
+    pub fn finish_history_request(&mut self, id: HistoryRequestId, received: usize) {
+        if let Some(request) = self.history.take(id) {
+            self.history.exhausted |= received == 0;
+            self.history.loaded_from = Some(
+                self.history
+                    .loaded_from
+                    .map_or(request.start, |loaded_from| loaded_from.min(request.start)),
+            );
+        }
+    }
+
+    /// the request failed, its range is requested again by the next `next_history_request`
+    pub fn cancel_history_request(&mut self, id: HistoryRequestId) {
+        self.history.take(id);
+    }
+
+    pub fn is_loading_history(&self) -> bool {
+        self.history.pending.is_some()
+    }
+
+    /// forget the loaded history, e.g. after switching the symbol or the interval
+    pub fn reset_history(&mut self) {
+        self.history.pending = None;
+        self.history.loaded_from = None;
+        self.history.exhausted = false;
+    }
+
+    /// fetch the next history request from the source and append the candles, returns the
+    /// number of received candles
+    pub fn load_history<S: CandleSource>(
+        &mut self,
+        source: &mut S,
+        candles: &mut Vec<Candle>,
+    ) -> Result<usize, S::Error> {
+        let Some(request) = self.next_history_request() else {
+            return Ok(0);
+        };
+        match source.fetch(&request) {
+            Ok(received) => {
+                let count = received.len();
+                candles.extend(received);
+                self.finish_history_request(request.id, count);
+                Ok(count)
+            }
+            Err(err) => {
+                self.cancel_history_request(request.id);
+                Err(err)
+            }
+        }
+    }
+
+    /// `load_history` for an `AsyncCandleSource`, the state is borrowed until the fetch is done.
+    /// To keep drawing meanwhile, fetch `next_history_request` on your runtime instead.
+    #[cfg(feature = "async")]
+    pub async fn load_history_async<S: AsyncCandleSource>(
+        &mut self,
+        source: &S,
+        candles: &mut Vec<Candle>,
+    ) -> Result<usize, S::Error> {
+        let Some(request) = self.next_history_request() else {
+            return Ok(0);
+        };
+        match source.fetch(&request).await {
+            Ok(received) => {
+                let count = received.len();
+                candles.extend(received);
+                self.finish_history_request(request.id, count);
+                Ok(count)
+            }
+            Err(err) => {
+                self.cancel_history_request(request.id);
+                Err(err)
+            }
+        }
+    }
+
+    /// follow the latest candle
     pub fn reset_cursor(&mut self) {
         self.cursor_timestamp = None;
     }
+
+    /// whether the chart follows the latest candle
+    pub fn is_live(&self) -> bool {
+        self.cursor_timestamp.is_none()
+    }
+
+    /// anchor at the candle under the cursor(the latest visible candle), then move the cursor
+    /// to measure the change to another candle
+    pub fn start_measure(&mut self) {
+        if let Some(info) = &self.info {
+            self.measure_anchor = Some(self.cursor_timestamp.unwrap_or(info.latest_timestamp));
+        }
+    }
+
+    pub fn stop_measure(&mut self) {
+        self.measure_anchor = None;
+        self.measurement = None;
+    }
+
+    pub fn is_measuring(&self) -> bool {
+        self.measure_anchor.is_some()
+    }
+
+    /// measurement between the anchor and the cursor by the last render
+    pub fn measurement(&self) -> Option<&Measurement> {
+        self.measurement.as_ref()
+    }
+
+    /// marker drawn at the buffer position(e.g. mouse position) by the last render
+    pub fn marker_at(&self, x: u16, y: u16) -> Option<&Marker> {
+        self.markers
+            .iter()
+            .find(|(position, _)| *position == (x, y))
+            .map(|(_, marker)| marker)
+    }
+
+    /// markers drawn by the last render with their buffer positions
+    pub fn rendered_markers(&self) -> impl Iterator<Item = ((u16, u16), &Marker)> {
+        self.markers
+            .iter()
+            .map(|(position, marker)| (*position, marker))
+    }
+
+    /// fix the y range instead of fitting it to the visible candles
+    pub fn lock_y_range(&mut self, min: f64, max: f64) {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        self.y_range = Some((OrderedFloat::from(min), OrderedFloat::from(max)));
+    }
+
+    /// current y range if it is locked
+    pub fn locked_y_range(&self) -> Option<(f64, f64)> {
+        self.y_range.map(|(min, max)| (*min, *max))
+    }
+
+    pub fn try_move_up(&mut self) {
+        self.pan_y(Y_PAN_RATIO);
+    }
+
+    pub fn try_move_down(&mut self) {
+        self.pan_y(-Y_PAN_RATIO);
+    }
+
+    pub fn zoom_y_in(&mut self) {
+        self.zoom_y(1. / Y_ZOOM_RATIO);
+    }
+
+    pub fn zoom_y_out(&mut self) {
+        self.zoom_y(Y_ZOOM_RATIO);
+    }
+
+    /// go back to fitting the y range to the visible candles
+    pub fn reset_y_range(&mut self) {
+        self.y_range = None;
+    }
+
+    pub(crate) fn current_y_range(&self) -> Option<(Float, Float)> {
+        self.y_range
+            .or_else(|| self.info.as_ref().map(|info| (info.y_min, info.y_max)))
+    }
+
+    fn pan_y(&mut self, ratio: f64) {
+        if let Some((min, max)) = self.current_y_range() {
+            let delta = (max - min) * ratio;
+            self.y_range = Some((min + delta, max + delta));
+        }
+    }
+
+    fn zoom_y(&mut self, ratio: f64) {
+        if let Some((min, max)) = self.current_y_range() {
+            let center = (min + max) / 2.;
+            let half = (max - min) / 2. * ratio;
+            self.y_range = Some((center - half, center + half));
+        }
+    }
+
+    /// show the crosshair at the cursor candle in the middle of the y range, or hide it
+    pub fn toggle_crosshair(&mut self) {
+        self.crosshair = match (self.crosshair, &self.info, self.current_y_range()) {
+            (None, Some(info), Some((min, max))) => Some(Anchor {
+                timestamp: self.cursor_timestamp.unwrap_or(info.latest_timestamp),
+                price: (min + max) / 2.,
+            }),
+            _ => None,
+        };
+    }
+
+    pub fn set_crosshair(&mut self, crosshair: Option<Anchor>) {
+        self.crosshair = crosshair;
+    }
+
+    pub fn crosshair(&self) -> Option<Anchor> {
+        self.crosshair
+    }
+
+    /// timestamp of the column and price at the middle of the row under the buffer position, if
+    /// it is on the candles of the last render
+    pub fn anchor_at(&self, x: u16, y: u16) -> Option<Anchor> {
+        let (area, info) = (self.chart_area?, self.info.as_ref()?);
+        if x < area.left() || x >= area.right() || y < area.top() || y >= area.bottom() {
+            return None;
+        }
+        let column = (x - area.x) as i64;
+        let row = (y - area.y) as f64;
+        let price = *info.y_max - (row + 0.5) / area.height as f64 * *(info.y_max - info.y_min);
+        Some(Anchor::new(
+            info.visible_range.0 + column * info.interval.as_millis(),
+            price,
+        ))
+    }
+
+    /// apply the action bound to the key or the mouse input, returns whether the chart has to be
+    /// redrawn
+    pub fn handle_event<E: IntoChartEvent + ?Sized>(&mut self, event: &E, keymap: &Keymap) -> bool {
+        let before = (self.cursor_timestamp, self.y_range, self.crosshair);
+        match event.to_chart_event() {
+            Some(ChartEvent::Key(key)) => {
+                if let Some(action) = keymap.action(&key) {
+                    self.apply(action);
+                }
+            }
+            Some(ChartEvent::Mouse(mouse)) => self.handle_mouse(mouse, keymap),
+            None => {}
+        }
+        before != (self.cursor_timestamp, self.y_range, self.crosshair)
+    }
+
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::Backward => self.try_move_backward(),
+            Action::Forward => self.try_move_forward(),
+            Action::PageBackward => self.page_backward(),
+            Action::PageForward => self.page_forward(),
+            Action::Start => self.move_to_start(),
+            Action::Live => self.reset_cursor(),
+            Action::PanUp => self.try_move_up(),
+            Action::PanDown => self.try_move_down(),
+            Action::ZoomIn => self.zoom_y_in(),
+            Action::ZoomOut => self.zoom_y_out(),
+            Action::ResetZoom => self.reset_y_range(),
+            Action::ToggleCrosshair => self.toggle_crosshair(),
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseInput, keymap: &Keymap) {
+        let position = (mouse.column, mouse.row);
+        let on_chart = self.anchor_at(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseKind::ScrollUp if mouse.ctrl => self.zoom_y_in(),
+            MouseKind::ScrollDown if mouse.ctrl => self.zoom_y_out(),
+            MouseKind::ScrollUp | MouseKind::ScrollLeft => {
+                self.move_bars(-keymap.bars_per_scroll())
+            }
+            MouseKind::ScrollDown | MouseKind::ScrollRight => {
+                self.move_bars(keymap.bars_per_scroll())
+            }
+            MouseKind::Down => self.drag_from = on_chart.map(|_| position),
+            MouseKind::Drag => match (self.drag_from, self.chart_area) {
+                (Some((x, y)), Some(area)) => {
+                    // the candles follow the pointer
+                    self.move_bars(x as i64 - mouse.column as i64);
+                    if y != mouse.row {
+                        self.pan_y((mouse.row as f64 - y as f64) / area.height as f64);
+                    }
+                    self.drag_from = Some(position);
+                }
+                _ => self.drag_from = on_chart.map(|_| position),
+            },
+            MouseKind::Up => self.drag_from = None,
+            MouseKind::Moved => {
+                self.drag_from = None;
+                if self.crosshair.is_some() && on_chart.is_some() {
+                    self.crosshair = on_chart;
+                }
+            }
+        }
+    }
+
+    pub fn add_drawing(
+        &mut self,
+        kind: DrawingKind,
+        start: Anchor,
+        end: Anchor,
+        color: Color,
+    ) -> DrawingId {
+        let id = self.next_drawing_id;
+        self.next_drawing_id += 1;
+        self.drawings.push(Drawing {
+            id,
+            kind,
+            start,
+            end,
+            color,
+        });
+        id
+    }
+
+    /// move both anchors of the drawing, returns false if there is no such drawing. The
+    /// timestamps stop at the range which can be rendered.
+    pub fn move_drawing(&mut self, id: DrawingId, delta_millis: i64, delta_price: f64) -> bool {
+        self.set_drawing_with(id, |drawing| {
+            for anchor in [&mut drawing.start, &mut drawing.end] {
+                anchor.timestamp = anchor
+                    .timestamp
+                    .saturating_add(delta_millis)
+                    .clamp(MIN_TIMESTAMP, MAX_TIMESTAMP);
+                anchor.price += delta_price;
+            }
+        })
+    }
+
+    /// replace the anchors of the drawing, returns false if there is no such drawing
+    pub fn set_drawing_anchors(&mut self, id: DrawingId, start: Anchor, end: Anchor) -> bool {
+        self.set_drawing_with(id, |drawing| {
+            drawing.start = start;
+            drawing.end = end;
+        })
+    }
+
+    pub fn remove_drawing(&mut self, id: DrawingId) -> Option<Drawing> {
+        let idx = self.drawings.iter().position(|d| d.id == id)?;
+        Some(self.drawings.remove(idx))
+    }
+
+    pub fn clear_drawings(&mut self) {
+        self.drawings.clear();
+    }
+
+    pub fn drawing(&self, id: DrawingId) -> Option<&Drawing> {
+        self.drawings.iter().find(|d| d.id == id)
+    }
+
+    pub fn drawings(&self) -> &[Drawing] {
+        &self.drawings
+    }
+
+    fn set_drawing_with(&mut self, id: DrawingId, f: impl FnOnce(&mut Drawing)) -> bool {
+        match self.drawings.iter_mut().find(|d| d.id == id) {
+            Some(drawing) => {
+                f(drawing);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use ratatui::style::Color;
+
+    use crate::{Anchor, Candle, CandleStickChart, CandleStickChartState, DrawingKind, Interval};
+
+    #[test]
+    fn serde() {
+        let mut state = CandleStickChartState::default();
+        let chart = CandleStickChart::new(Interval::OneMinute).candles(vec![
+            Candle::new(0, 1., 2., 0., 1.).unwrap(),
+            Candle::new(60000, 1., 2., 0., 1.).unwrap(),
+        ]);
+        crate::render_to_buffer(chart, 40, 10, &mut state);
+        state.try_move_backward();
+        state.lock_y_range(0., 4.);
+        state.add_drawing(
+            DrawingKind::TrendLine,
+            Anchor::new(0, 1.),
+            Anchor::new(60000, 2.),
+            Color::Red,
+        );
+
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(
+            json,
+            r#"{"cursor":0,"y_range":[0.0,4.0],"drawings":[{"id":0,"kind":"TrendLine","start":{"timestamp":0,"price":1.0},"end":{"timestamp":60000,"price":2.0},"color":"Red"}],"next_drawing_id":1,"measure_anchor":null}"#
+        );
+
+        let restored: CandleStickChartState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.cursor_timestamp, Some(0));
+        assert_eq!(restored.locked_y_range(), Some((0., 4.)));
+        assert_eq!(restored.drawings(), state.drawings());
+        assert_eq!(
+            serde_json::from_str::<CandleStickChartState>("{}").unwrap(),
+            CandleStickChartState::default()
+        );
+
+        assert_eq!(
+            serde_json::to_string(&Interval::FourHours).unwrap(),
+            r#""4h""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Interval>(r#""15m""#).unwrap(),
+            Interval::FifteenMinutes
+        );
+        assert!(serde_json::from_str::<Interval>(r#""7m""#).is_err());
+        assert_eq!(
+            serde_json::from_str::<Candle>(
+                r#"{"timestamp":0,"open":1.0,"high":2.0,"low":0.0,"close":1.0}"#
+            )
+            .unwrap(),
+            Candle::new(0, 1., 2., 0., 1.).unwrap()
+        );
+    }
 }
@@ -0,0 +1,313 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    Action, Anchor, CandleStickChartState, ChartEvent, Float, IntoChartEvent, Keymap, MouseInput,
+    MouseKind,
+};
+
+/// Charts which pan, zoom and move the crosshair together. The charts are matched by time rather
+/// than by bar, so charts of different intervals show the same moment, and by the position in
+/// the y range, so charts of different symbols zoom alike.
+///
+/// Render each chart with `&mut group[i]` and pass the input to `handle_event`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChartGroup {
+    states: Vec<CandleStickChartState>,
+    /// chart receiving the keys
+    focus: usize,
+}
+
+impl ChartGroup {
+    pub fn new(len: usize) -> Self {
+        Self::from_states(vec![CandleStickChartState::default(); len])
+    }
+
+    pub fn from_states(states: Vec<CandleStickChartState>) -> Self {
+        Self { states, focus: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn states(&self) -> &[CandleStickChartState] {
+        &self.states
+    }
+
+    pub fn focus(&self) -> usize {
+        self.focus
+    }
+
+    /// out of range indexes are ignored
+    pub fn set_focus(&mut self, index: usize) {
+        if index < self.states.len() {
+            self.focus = index;
+        }
+    }
+
+    /// keys go to the focused chart and the mouse to the chart under the pointer, which gets the
+    /// focus on a click, then the other charts follow. Returns whether the charts have to be
+    /// redrawn.
+    pub fn handle_event<E: IntoChartEvent + ?Sized>(&mut self, event: &E, keymap: &Keymap) -> bool {
+        let Some(event) = event.to_chart_event() else {
+            return false;
+        };
+        let index = match event {
+            ChartEvent::Key(_) => Some(self.focus),
+            ChartEvent::Mouse(mouse) => self.chart_under(mouse),
+        };
+        let Some(index) = index.filter(|index| *index < self.states.len()) else {
+            return false;
+        };
+        if let ChartEvent::Mouse(MouseInput {
+            kind: MouseKind::Down,
+            ..
+        }) = event
+        {
+            self.focus = index;
+        }
+
+        let before = YRange::of(&self.states[index]);
+        let changed = self.states[index].handle_event(&event, keymap);
+        if changed {
+            let zoom = before.zoom(&self.states[index]);
+            self.follow(index, zoom);
+        }
+        changed
+    }
+
+    /// apply the action to the focused chart, then the other charts follow
+    pub fn apply(&mut self, action: Action) {
+        if let Some(state) = self.states.get_mut(self.focus) {
+            let before = YRange::of(state);
+            state.apply(action);
+            let zoom = before.zoom(state);
+            self.follow(self.focus, zoom);
+        }
+    }
+
+    /// move the other charts to the cursor and the crosshair of the chart, e.g. after calling
+    /// `jump_to` on it, the y ranges are kept
+    pub fn sync_from(&mut self, index: usize) {
+        if index < self.states.len() {
+            self.follow(index, None);
+        }
+    }
+
+    /// the dragging chart, or the chart whose candles are under the pointer
+    fn chart_under(&self, mouse: MouseInput) -> Option<usize> {
+        self.states
+            .iter()
+            .position(|state| state.drag_from.is_some())
+            .or_else(|| {
+                self.states
+                    .iter()
+                    .position(|state| state.anchor_at(mouse.column, mouse.row).is_some())
+            })
+    }
+
+    /// move the other charts to the chart at the index
+    fn follow(&mut self, index: usize, zoom: Option<Zoom>) {
+        let source = &self.states[index];
+        let Some(interval) = source.interval() else {
+            return;
+        };
+        // last moment of the cursor candle
+        let cursor = source
+            .cursor_timestamp
+            .map(|cursor| cursor + interval.as_millis() - 1);
+        let crosshair = source
+            .crosshair
+            .zip(source.current_y_range())
+            .map(|(anchor, y_range)| (anchor.timestamp, relative(y_range, anchor.price)));
+
+        for (i, state) in self.states.iter_mut().enumerate() {
+            let Some(interval) = state.interval().filter(|_| i != index) else {
+                continue;
+            };
+            match cursor {
+                Some(cursor) => state.jump_to(cursor),
+                None => state.reset_cursor(),
+            }
+            match zoom {
+                Some(Zoom::Reset) => state.reset_y_range(),
+                Some(Zoom::To(min, max)) => {
+                    if let Some(y_range) = state.current_y_range() {
+                        state.y_range = Some((absolute(y_range, min), absolute(y_range, max)));
+                    }
+                }
+                None => {}
+            }
+            state.crosshair = match (crosshair, state.current_y_range()) {
+                (Some((timestamp, position)), Some(y_range)) => Some(Anchor {
+                    timestamp: interval.align(timestamp),
+                    price: absolute(y_range, position),
+                }),
+                _ => None,
+            };
+        }
+    }
+}
+
+/// change of the y range relative to the range before it
+#[derive(Debug, Clone, Copy)]
+enum Zoom {
+    Reset,
+    /// new bottom and top as positions in the old range
+    To(f64, f64),
+}
+
+/// y range of a chart before a change
+struct YRange {
+    locked: Option<(Float, Float)>,
+    current: Option<(Float, Float)>,
+}
+
+impl YRange {
+    fn of(state: &CandleStickChartState) -> Self {
+        Self {
+            locked: state.y_range,
+            current: state.current_y_range(),
+        }
+    }
+
+    fn zoom(&self, state: &CandleStickChartState) -> Option<Zoom> {
+        match (state.y_range, self.current) {
+            (after, _) if after == self.locked => None,
+            (None, _) => Some(Zoom::Reset),
+            (Some((min, max)), Some(before)) => {
+                Some(Zoom::To(relative(before, min), relative(before, max)))
+            }
+            (Some(_), None) => None,
+        }
+    }
+}
+
+/// position of the price in the range, 0 at the bottom and 1 at the top
+fn relative((min, max): (Float, Float), price: Float) -> f64 {
+    if min == max {
+        0.5
+    } else {
+        *((price - min) / (max - min))
+    }
+}
+
+fn absolute((min, max): (Float, Float), position: f64) -> Float {
+    min + (max - min) * position
+}
+
+impl Index<usize> for ChartGroup {
+    type Output = CandleStickChartState;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.states[index]
+    }
+}
+
+impl IndexMut<usize> for ChartGroup {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.states[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::StatefulWidget};
+
+    use super::ChartGroup;
+    use crate::{
+        Anchor, Candle, CandleStickChart, ChartEvent, Interval, Key, KeyPress, Keymap, MouseInput,
+        MouseKind,
+    };
+
+    /// ten hours of 1m candles on the top and 5m candles on the bottom, 17 columns each
+    fn render(group: &mut ChartGroup) {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 30, 20));
+        for (i, interval) in [Interval::OneMinute, Interval::FiveMinutes]
+            .into_iter()
+            .enumerate()
+        {
+            let candles = (0..36000000)
+                .step_by(interval.as_millis() as usize)
+                .map(|t| Candle::new(t, 1., 2., 0., 1.).unwrap())
+                .collect();
+            CandleStickChart::new(interval)
+                .candles(candles)
+                .now(0)
+                .render(
+                    Rect::new(0, i as u16 * 10, 30, 10),
+                    &mut buffer,
+                    &mut group[i],
+                );
+        }
+    }
+
+    fn key(key: impl Into<KeyPress>) -> ChartEvent {
+        ChartEvent::Key(key.into())
+    }
+
+    fn mouse(kind: MouseKind, column: u16, row: u16) -> ChartEvent {
+        ChartEvent::Mouse(MouseInput {
+            kind,
+            column,
+            row,
+            ctrl: false,
+        })
+    }
+
+    #[test]
+    fn test_follow_by_time() {
+        let keymap = Keymap::default();
+        let mut group = ChartGroup::new(2);
+        render(&mut group);
+
+        assert!(group.handle_event(&key(Key::PageUp), &keymap));
+        assert_eq!(group[0].cursor_timestamp, Some(34920000));
+        // the 5m candle containing 34979999
+        assert_eq!(group[1].cursor_timestamp, Some(34800000));
+        render(&mut group);
+
+        // scrolling the bottom chart 3 candles back
+        assert!(group.handle_event(&mouse(MouseKind::ScrollUp, 20, 12), &keymap));
+        assert_eq!(group[1].cursor_timestamp, Some(33900000));
+        assert_eq!(group[0].cursor_timestamp, Some(34140000));
+        assert_eq!(group.focus(), 0);
+        assert!(!group.handle_event(&mouse(MouseKind::Down, 20, 12), &keymap));
+        assert_eq!(group.focus(), 1);
+
+        assert!(group.handle_event(&key('G'), &keymap));
+        assert_eq!(group[0].cursor_timestamp, None);
+        assert_eq!(group[1].cursor_timestamp, None);
+
+        // outside of both charts
+        group.handle_event(&mouse(MouseKind::Up, 20, 12), &keymap);
+        assert!(!group.handle_event(&mouse(MouseKind::ScrollUp, 0, 12), &keymap));
+    }
+
+    #[test]
+    fn test_zoom_and_crosshair() {
+        let keymap = Keymap::default();
+        let mut group = ChartGroup::new(2);
+        render(&mut group);
+
+        assert!(group.handle_event(&key('+'), &keymap));
+        assert!(group[0].locked_y_range().is_some());
+        assert_eq!(group[0].locked_y_range(), group[1].locked_y_range());
+        assert!(group.handle_event(&key('0'), &keymap));
+        assert_eq!(group[1].locked_y_range(), None);
+
+        render(&mut group);
+        assert!(group.handle_event(&key('c'), &keymap));
+        assert_eq!(group[0].crosshair(), Some(Anchor::new(35940000, 1.)));
+        assert_eq!(group[1].crosshair(), Some(Anchor::new(35700000, 1.)));
+
+        group[1].jump_to(0);
+        group.sync_from(1);
+        assert_eq!(group[0].cursor_timestamp, Some(240000));
+        assert_eq!(group[0].crosshair(), Some(Anchor::new(35700000, 1.)));
+    }
+}
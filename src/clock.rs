@@ -0,0 +1,29 @@
+use chrono::Utc;
+
+use crate::Replay;
+
+/// Source of the current time in milliseconds, read once per render
+pub trait Clock {
+    fn now(&self) -> i64;
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        Utc::now().timestamp_millis()
+    }
+}
+
+impl<F: Fn() -> i64> Clock for F {
+    fn now(&self) -> i64 {
+        self()
+    }
+}
+
+impl Clock for Replay {
+    fn now(&self) -> i64 {
+        Replay::now(self)
+    }
+}
@@ -0,0 +1,174 @@
+use ordered_float::OrderedFloat;
+use ratatui::style::Color;
+
+use crate::{Candle, Float, Interval};
+
+/// Another series, e.g. a symbol or an index, drawn as a line over the candles. Both are
+/// normalized to the percent change since the first visible candle and share the y axis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub(crate) name: String,
+    /// timestamps and prices sorted by timestamp
+    pub(crate) points: Vec<(i64, Float)>,
+    pub(crate) color: Color,
+}
+
+impl Comparison {
+    /// non-finite prices and timestamps which cannot be rendered are skipped
+    pub fn new(name: impl Into<String>, points: impl IntoIterator<Item = (i64, f64)>) -> Self {
+        let mut points = points
+            .into_iter()
+            .filter(|(timestamp, price)| {
+                price.is_finite() && Candle::is_renderable_timestamp(*timestamp)
+            })
+            .map(|(timestamp, price)| (timestamp, OrderedFloat::from(price)))
+            .collect::<Vec<_>>();
+        points.sort_by_key(|(timestamp, _)| *timestamp);
+
+        Self {
+            name: name.into(),
+            points,
+            color: Color::Reset,
+        }
+    }
+
+    /// closes of the candles
+    pub fn from_candles(name: impl Into<String>, candles: &[Candle]) -> Self {
+        Self::new(
+            name,
+            candles
+                .iter()
+                .map(|candle| (candle.timestamp, *candle.close)),
+        )
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// highest price divided by the lowest, `None` without a positive price
+    pub(crate) fn ratio(&self) -> Option<Float> {
+        let prices = self.points.iter().map(|(_, price)| *price);
+        let min = prices.clone().filter(|price| **price > 0.).min()?;
+        Some(prices.max()? / min)
+    }
+
+    /// the last price in each bucket of the interval
+    fn buckets(&self, interval: Interval) -> Vec<(i64, Float)> {
+        let mut buckets: Vec<(i64, Float)> = Vec::new();
+        for &(timestamp, price) in &self.points {
+            let bucket = interval.align(timestamp);
+            match buckets.last_mut() {
+                Some(last) if last.0 == bucket => last.1 = price,
+                _ => buckets.push((bucket, price)),
+            }
+        }
+        buckets
+    }
+
+    /// prices of the buckets around `start..=end` in units of the main series, whose price at
+    /// `base_timestamp` is `base`. The series is based on its last price at or before
+    /// `base_timestamp`, or on its first price after it if it starts later.
+    pub(crate) fn scale(
+        &self,
+        interval: Interval,
+        (start, end): (i64, i64),
+        base_timestamp: i64,
+        base: Float,
+    ) -> Option<ScaledComparison> {
+        let buckets = self.buckets(interval);
+        let own_base = buckets
+            .iter()
+            .rev()
+            .find(|(timestamp, _)| *timestamp <= base_timestamp)
+            .or_else(|| buckets.first())
+            .map(|(_, price)| *price)
+            .filter(|price| **price > 0.)?;
+
+        let margin = interval.as_millis();
+        let points = buckets
+            .into_iter()
+            .filter(|(timestamp, _)| {
+                *timestamp >= start.saturating_sub(margin)
+                    && *timestamp <= end.saturating_add(margin)
+            })
+            .map(|(timestamp, price)| (timestamp, base * price / own_base))
+            .collect::<Vec<_>>();
+        let change = points
+            .iter()
+            .rev()
+            .find(|(timestamp, _)| *timestamp <= end)
+            .map(|(_, price)| (*price / base - 1.) * 100.);
+
+        Some(ScaledComparison {
+            name: self.name.clone(),
+            color: self.color,
+            points,
+            change,
+        })
+    }
+}
+
+/// Comparison prepared for a render
+pub(crate) struct ScaledComparison {
+    pub name: String,
+    pub color: Color,
+    /// prices in units of the main series
+    pub points: Vec<(i64, Float)>,
+    /// percent change at the last visible price
+    pub change: Option<Float>,
+}
+
+impl ScaledComparison {
+    /// prices of the visible buckets
+    pub fn visible(&self, (start, end): (i64, i64)) -> impl Iterator<Item = Float> + '_ {
+        self.points
+            .iter()
+            .filter(move |(timestamp, _)| *timestamp >= start && *timestamp <= end)
+            .map(|(_, price)| *price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Comparison;
+    use crate::{Float, Interval};
+
+    #[test]
+    fn test_scale() {
+        let comparison = Comparison::new(
+            "ETH",
+            [(0, 10.), (30000, 20.), (60000, f64::NAN), (120000, 30.)],
+        );
+        let scaled = comparison
+            .scale(Interval::OneMinute, (60000, 120000), 60000, 100.into())
+            .unwrap();
+        // based on the last price of the bucket before the gap
+        assert_eq!(
+            scaled.points,
+            vec![(0, Float::from(100.)), (120000, Float::from(150.))]
+        );
+        assert_eq!(scaled.change, Some(Float::from(50.)));
+        assert_eq!(
+            scaled.visible((60000, 120000)).collect::<Vec<_>>(),
+            vec![Float::from(150.)]
+        );
+
+        // starting after the first visible candle
+        let scaled = comparison
+            .scale(Interval::OneMinute, (-60000, 120000), -60000, 100.into())
+            .unwrap();
+        assert_eq!(scaled.points[0], (0, Float::from(100.)));
+
+        // out of range timestamps are skipped
+        assert_eq!(
+            Comparison::new("", [(i64::MIN, 1.), (0, 2.), (i64::MAX, 3.)]).points,
+            vec![(0, Float::from(2.))]
+        );
+
+        assert!(Comparison::new("", [(0, -1.)])
+            .scale(Interval::OneMinute, (0, 0), 0, 1.into())
+            .is_none());
+    }
+}
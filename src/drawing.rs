@@ -0,0 +1,147 @@
+use ordered_float::OrderedFloat;
+use ratatui::style::Color;
+
+use crate::{projection::Projection, symbols::*, Float};
+
+/// Fibonacci retracement ratios drawn between the two anchors
+const FIBONACCI_RATIOS: [f64; 7] = [0., 0.236, 0.382, 0.5, 0.618, 0.786, 1.];
+
+pub type DrawingId = u64;
+
+/// Point attached to the data, so that drawings follow candles while scrolling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Anchor {
+    pub timestamp: i64,
+    pub price: Float,
+}
+
+impl Anchor {
+    pub fn new(timestamp: i64, price: f64) -> Self {
+        Self {
+            timestamp,
+            price: OrderedFloat::from(price),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawingKind {
+    /// line segment between the anchors
+    TrendLine,
+    /// horizontal line from the start anchor to the right edge, the end anchor is ignored
+    HorizontalRay,
+    /// box with the anchors at opposite corners
+    Rectangle,
+    /// retracement levels between the prices of the anchors
+    Fibonacci,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Drawing {
+    pub(crate) id: DrawingId,
+    pub kind: DrawingKind,
+    pub start: Anchor,
+    pub end: Anchor,
+    pub color: Color,
+}
+
+impl Drawing {
+    pub fn id(&self) -> DrawingId {
+        self.id
+    }
+
+    /// cells and symbols of the drawing
+    pub(crate) fn render(&self, projection: &Projection) -> Vec<((u16, u16), String)> {
+        let from = (
+            projection.column(self.start.timestamp),
+            projection.row(self.start.price),
+        );
+        let to = (
+            projection.column(self.end.timestamp),
+            projection.row(self.end.price),
+        );
+        let right = projection.area.width as i64 - 1;
+
+        match self.kind {
+            DrawingKind::TrendLine => {
+                let symbol = line_symbol(from, to);
+                with_symbol(projection.line(from, to), symbol)
+            }
+            DrawingKind::HorizontalRay => {
+                with_symbol(projection.line(from, (right, from.1)), DRAWING_HORIZONTAL)
+            }
+            DrawingKind::Rectangle => {
+                let (left, right) = (from.0.min(to.0), from.0.max(to.0));
+                let (top, bottom) = (from.1.min(to.1), from.1.max(to.1));
+                let mut result = Vec::new();
+                for row in [top, bottom] {
+                    result.extend(with_symbol(
+                        projection.line((left, row), (right, row)),
+                        DRAWING_HORIZONTAL,
+                    ));
+                }
+                for column in [left, right] {
+                    result.extend(with_symbol(
+                        projection.line((column, top), (column, bottom)),
+                        DRAWING_VERTICAL,
+                    ));
+                }
+                for (column, row, symbol) in [
+                    (left, top, DRAWING_TOP_LEFT),
+                    (right, top, DRAWING_TOP_RIGHT),
+                    (left, bottom, DRAWING_BOTTOM_LEFT),
+                    (right, bottom, DRAWING_BOTTOM_RIGHT),
+                ] {
+                    if let Some(position) = projection.position(column, row) {
+                        result.push((position, symbol.to_string()));
+                    }
+                }
+                result
+            }
+            DrawingKind::Fibonacci => {
+                let left = from.0.min(to.0);
+                let mut result = Vec::new();
+                for ratio in FIBONACCI_RATIOS {
+                    let price = self.end.price + (self.start.price - self.end.price) * ratio;
+                    let row = projection.row(price);
+                    result.extend(with_symbol(
+                        projection.line((left, row), (right, row)),
+                        DRAWING_DOTTED,
+                    ));
+
+                    let label = format!("{:.3}", ratio);
+                    for (i, char) in label.chars().enumerate() {
+                        if let Some(position) = projection.position(left + i as i64, row) {
+                            result.push((position, char.to_string()));
+                        }
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+fn with_symbol(cells: Vec<(u16, u16)>, symbol: &str) -> Vec<((u16, u16), String)> {
+    cells
+        .into_iter()
+        .map(|cell| (cell, symbol.to_string()))
+        .collect()
+}
+
+fn line_symbol(from: (i64, i64), to: (i64, i64)) -> &'static str {
+    let dx = (to.0 - from.0).abs();
+    let dy = (to.1 - from.1).abs();
+    if dy * 2 <= dx {
+        DRAWING_HORIZONTAL
+    } else if dx * 2 <= dy {
+        DRAWING_VERTICAL
+    } else if (to.0 - from.0).signum() == (to.1 - from.1).signum() {
+        DRAWING_FALLING
+    } else {
+        DRAWING_RISING
+    }
+}
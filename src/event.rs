@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+
+/// Backend independent key, converted from crossterm, termion or termwiz events with the feature
+/// of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    Left,
+    Right,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Esc,
+    Enter,
+}
+
+/// Key with its modifiers, shift is part of the char
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyPress {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl KeyPress {
+    pub fn ctrl(key: impl Into<Key>) -> Self {
+        Self {
+            ctrl: true,
+            ..Self::from(key.into())
+        }
+    }
+
+    pub fn alt(key: impl Into<Key>) -> Self {
+        Self {
+            alt: true,
+            ..Self::from(key.into())
+        }
+    }
+}
+
+impl From<Key> for KeyPress {
+    fn from(key: Key) -> Self {
+        Self {
+            key,
+            ctrl: false,
+            alt: false,
+        }
+    }
+}
+
+impl From<char> for Key {
+    fn from(c: char) -> Self {
+        Key::Char(c)
+    }
+}
+
+impl From<char> for KeyPress {
+    fn from(c: char) -> Self {
+        Key::Char(c).into()
+    }
+}
+
+/// Left button presses, drags and releases, pointer moves and wheel scrolls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseKind {
+    Down,
+    Drag,
+    Up,
+    Moved,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+/// Mouse event at the zero based buffer position
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseInput {
+    pub kind: MouseKind,
+    pub column: u16,
+    pub row: u16,
+    pub ctrl: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartEvent {
+    Key(KeyPress),
+    Mouse(MouseInput),
+}
+
+/// Events which `CandleStickChartState::handle_event` understands, other events are ignored
+pub trait IntoChartEvent {
+    fn to_chart_event(&self) -> Option<ChartEvent>;
+}
+
+impl IntoChartEvent for ChartEvent {
+    fn to_chart_event(&self) -> Option<ChartEvent> {
+        Some(*self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// one bar to older candles
+    Backward,
+    /// one bar to newer candles
+    Forward,
+    /// the visible width to older candles
+    PageBackward,
+    /// the visible width to newer candles
+    PageForward,
+    /// the oldest candle at the left edge
+    Start,
+    /// follow the latest candle
+    Live,
+    PanUp,
+    PanDown,
+    ZoomIn,
+    ZoomOut,
+    /// fit the y range to the visible candles again
+    ResetZoom,
+    ToggleCrosshair,
+}
+
+/// Key bindings of `CandleStickChartState::handle_event`.
+///
+/// The mouse wheel scrolls `scroll_bars` bars, up or left to older candles, or zooms with ctrl.
+/// Dragging with the left button pans, and moving the pointer moves the shown crosshair.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyPress, Action>,
+    scroll_bars: u16,
+}
+
+impl Default for Keymap {
+    /// arrows and hjkl, page up/down and ctrl-b/f, home and end/G, +/-/0 for zoom and c for the
+    /// crosshair, `g` is left to applications, e.g. for a grid toggle
+    fn default() -> Self {
+        let bindings = [
+            (Key::Left.into(), Action::Backward),
+            ('h'.into(), Action::Backward),
+            (Key::Right.into(), Action::Forward),
+            ('l'.into(), Action::Forward),
+            (Key::PageUp.into(), Action::PageBackward),
+            (KeyPress::ctrl('b'), Action::PageBackward),
+            (Key::PageDown.into(), Action::PageForward),
+            (KeyPress::ctrl('f'), Action::PageForward),
+            (Key::Home.into(), Action::Start),
+            (Key::End.into(), Action::Live),
+            ('G'.into(), Action::Live),
+            (Key::Up.into(), Action::PanUp),
+            ('k'.into(), Action::PanUp),
+            (Key::Down.into(), Action::PanDown),
+            ('j'.into(), Action::PanDown),
+            ('+'.into(), Action::ZoomIn),
+            ('='.into(), Action::ZoomIn),
+            ('-'.into(), Action::ZoomOut),
+            ('0'.into(), Action::ResetZoom),
+            ('c'.into(), Action::ToggleCrosshair),
+        ];
+        Self {
+            bindings: bindings.into_iter().collect(),
+            scroll_bars: 3,
+        }
+    }
+}
+
+impl Keymap {
+    /// without any key binding
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+            ..Self::default()
+        }
+    }
+
+    pub fn bind(mut self, key: impl Into<KeyPress>, action: Action) -> Self {
+        self.bindings.insert(key.into(), action);
+        self
+    }
+
+    pub fn unbind(mut self, key: impl Into<KeyPress>) -> Self {
+        self.bindings.remove(&key.into());
+        self
+    }
+
+    pub fn scroll_bars(mut self, bars: u16) -> Self {
+        self.scroll_bars = bars;
+        self
+    }
+
+    pub fn action(&self, key: &KeyPress) -> Option<Action> {
+        self.bindings.get(key).copied()
+    }
+
+    pub(crate) fn bars_per_scroll(&self) -> i64 {
+        self.scroll_bars as i64
+    }
+}
+
+#[cfg(feature = "crossterm")]
+impl IntoChartEvent for crossterm::event::Event {
+    fn to_chart_event(&self) -> Option<ChartEvent> {
+        use crossterm::event::{
+            Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
+        };
+
+        match self {
+            Event::Key(event) if event.kind != KeyEventKind::Release => {
+                let key = match event.code {
+                    KeyCode::Char(c) => Key::Char(c),
+                    KeyCode::Left => Key::Left,
+                    KeyCode::Right => Key::Right,
+                    KeyCode::Up => Key::Up,
+                    KeyCode::Down => Key::Down,
+                    KeyCode::PageUp => Key::PageUp,
+                    KeyCode::PageDown => Key::PageDown,
+                    KeyCode::Home => Key::Home,
+                    KeyCode::End => Key::End,
+                    KeyCode::Esc => Key::Esc,
+                    KeyCode::Enter => Key::Enter,
+                    _ => return None,
+                };
+                Some(ChartEvent::Key(KeyPress {
+                    key,
+                    ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+                    alt: event.modifiers.contains(KeyModifiers::ALT),
+                }))
+            }
+            Event::Mouse(event) => {
+                let kind = match event.kind {
+                    MouseEventKind::Down(MouseButton::Left) => MouseKind::Down,
+                    MouseEventKind::Drag(MouseButton::Left) => MouseKind::Drag,
+                    MouseEventKind::Up(MouseButton::Left) => MouseKind::Up,
+                    MouseEventKind::Moved => MouseKind::Moved,
+                    MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+                    MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+                    MouseEventKind::ScrollLeft => MouseKind::ScrollLeft,
+                    MouseEventKind::ScrollRight => MouseKind::ScrollRight,
+                    _ => return None,
+                };
+                Some(ChartEvent::Mouse(MouseInput {
+                    kind,
+                    column: event.column,
+                    row: event.row,
+                    ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "termion")]
+impl IntoChartEvent for termion::event::Event {
+    fn to_chart_event(&self) -> Option<ChartEvent> {
+        use termion::event::{Event, Key as TermionKey, MouseButton, MouseEvent};
+
+        match self {
+            Event::Key(key) => {
+                let key = match *key {
+                    TermionKey::Char('\n') => Key::Enter.into(),
+                    TermionKey::Char(c) => c.into(),
+                    TermionKey::Ctrl(c) => KeyPress::ctrl(c),
+                    TermionKey::Alt(c) => KeyPress::alt(c),
+                    TermionKey::Left => Key::Left.into(),
+                    TermionKey::Right => Key::Right.into(),
+                    TermionKey::Up => Key::Up.into(),
+                    TermionKey::Down => Key::Down.into(),
+                    TermionKey::PageUp => Key::PageUp.into(),
+                    TermionKey::PageDown => Key::PageDown.into(),
+                    TermionKey::Home => Key::Home.into(),
+                    TermionKey::End => Key::End.into(),
+                    TermionKey::Esc => Key::Esc.into(),
+                    _ => return None,
+                };
+                Some(ChartEvent::Key(key))
+            }
+            // one based coordinates, no pointer moves without a pressed button
+            Event::Mouse(event) => {
+                let (kind, x, y) = match *event {
+                    MouseEvent::Press(button, x, y) => {
+                        let kind = match button {
+                            MouseButton::Left => MouseKind::Down,
+                            MouseButton::WheelUp => MouseKind::ScrollUp,
+                            MouseButton::WheelDown => MouseKind::ScrollDown,
+                            _ => return None,
+                        };
+                        (kind, x, y)
+                    }
+                    MouseEvent::Hold(x, y) => (MouseKind::Drag, x, y),
+                    MouseEvent::Release(x, y) => (MouseKind::Up, x, y),
+                };
+                Some(ChartEvent::Mouse(MouseInput {
+                    kind,
+                    column: x.saturating_sub(1),
+                    row: y.saturating_sub(1),
+                    ctrl: false,
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "termwiz")]
+impl IntoChartEvent for termwiz::input::InputEvent {
+    fn to_chart_event(&self) -> Option<ChartEvent> {
+        use termwiz::input::{InputEvent, KeyCode, Modifiers, MouseButtons};
+
+        match self {
+            InputEvent::Key(event) => {
+                let key = match event.key {
+                    KeyCode::Char(c) => Key::Char(c),
+                    KeyCode::LeftArrow => Key::Left,
+                    KeyCode::RightArrow => Key::Right,
+                    KeyCode::UpArrow => Key::Up,
+                    KeyCode::DownArrow => Key::Down,
+                    KeyCode::PageUp => Key::PageUp,
+                    KeyCode::PageDown => Key::PageDown,
+                    KeyCode::Home => Key::Home,
+                    KeyCode::End => Key::End,
+                    KeyCode::Escape => Key::Esc,
+                    KeyCode::Enter => Key::Enter,
+                    _ => return None,
+                };
+                Some(ChartEvent::Key(KeyPress {
+                    key,
+                    ctrl: event.modifiers.contains(Modifiers::CTRL),
+                    alt: event.modifiers.contains(Modifiers::ALT),
+                }))
+            }
+            // one based coordinates, the pressed buttons instead of presses and releases, so a
+            // held left button is a drag and no button ends it
+            InputEvent::Mouse(event) => {
+                let buttons = event.mouse_buttons;
+                let positive = buttons.contains(MouseButtons::WHEEL_POSITIVE);
+                let kind = if buttons.contains(MouseButtons::VERT_WHEEL) {
+                    if positive {
+                        MouseKind::ScrollUp
+                    } else {
+                        MouseKind::ScrollDown
+                    }
+                } else if buttons.contains(MouseButtons::HORZ_WHEEL) {
+                    if positive {
+                        MouseKind::ScrollLeft
+                    } else {
+                        MouseKind::ScrollRight
+                    }
+                } else if buttons.contains(MouseButtons::LEFT) {
+                    MouseKind::Drag
+                } else if buttons.is_empty() {
+                    MouseKind::Moved
+                } else {
+                    return None;
+                };
+                Some(ChartEvent::Mouse(MouseInput {
+                    kind,
+                    column: event.x.saturating_sub(1),
+                    row: event.y.saturating_sub(1),
+                    ctrl: event.modifiers.contains(Modifiers::CTRL),
+                }))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, ChartEvent, Key, KeyPress, Keymap, MouseInput, MouseKind};
+    use crate::{
+        render_to_buffer, Anchor, Candle, CandleStickChart, CandleStickChartState, Interval,
+    };
+
+    fn render(state: &mut CandleStickChartState) {
+        let candles = (0..100)
+            .map(|i| Candle::new(i * 60000, 1., 2., 0., 1.).unwrap())
+            .collect();
+        let chart = CandleStickChart::new(Interval::OneMinute).candles(candles);
+        render_to_buffer(chart, 30, 10, state);
+    }
+
+    fn key(key: impl Into<KeyPress>) -> ChartEvent {
+        ChartEvent::Key(key.into())
+    }
+
+    fn mouse(kind: MouseKind, column: u16, row: u16) -> ChartEvent {
+        ChartEvent::Mouse(MouseInput {
+            kind,
+            column,
+            row,
+            ctrl: false,
+        })
+    }
+
+    #[test]
+    fn test_keymap() {
+        let keymap = Keymap::default()
+            .bind(KeyPress::ctrl(Key::Left), Action::Live)
+            .unbind('h');
+        assert_eq!(keymap.action(&'l'.into()), Some(Action::Forward));
+        assert_eq!(keymap.action(&'h'.into()), None);
+        assert_eq!(
+            keymap.action(&KeyPress::ctrl(Key::Left)),
+            Some(Action::Live)
+        );
+        assert_eq!(Keymap::empty().action(&Key::Left.into()), None);
+        // left to applications, the examples and the cli toggle the grid with it
+        assert_eq!(Keymap::default().action(&'g'.into()), None);
+    }
+
+    #[test]
+    fn test_keys() {
+        let keymap = Keymap::default();
+        let mut state = CandleStickChartState::default();
+        render(&mut state);
+
+        // 17 columns ending at 5940000
+        assert!(state.handle_event(&key(Key::PageUp), &keymap));
+        assert_eq!(state.cursor_timestamp, Some(4920000));
+        assert!(state.handle_event(&key('h'), &keymap));
+        assert_eq!(state.cursor_timestamp, Some(4860000));
+        assert!(state.handle_event(&key(Key::Home), &keymap));
+        assert_eq!(state.cursor_timestamp, Some(960000));
+        assert!(!state.handle_event(&key(Key::Home), &keymap));
+        assert!(!state.handle_event(&key('g'), &keymap));
+        assert!(!state.handle_event(&key('x'), &keymap));
+        assert!(state.handle_event(&key('G'), &keymap));
+        assert_eq!(state.cursor_timestamp, None);
+
+        assert!(state.handle_event(&key('+'), &keymap));
+        assert!(state.locked_y_range().is_some());
+        assert!(state.handle_event(&key('0'), &keymap));
+        assert!(state.handle_event(&key('c'), &keymap));
+        assert_eq!(state.crosshair(), Some(Anchor::new(5940000, 1.)));
+    }
+
+    #[test]
+    fn test_mouse() {
+        let keymap = Keymap::default().scroll_bars(5);
+        let mut state = CandleStickChartState::default();
+        render(&mut state);
+
+        assert!(state.handle_event(&mouse(MouseKind::ScrollUp, 20, 2), &keymap));
+        assert_eq!(state.cursor_timestamp, Some(5640000));
+
+        // dragging the candles 2 columns to the right shows older candles
+        assert!(!state.handle_event(&mouse(MouseKind::Down, 20, 2), &keymap));
+        assert!(state.handle_event(&mouse(MouseKind::Drag, 22, 2), &keymap));
+        assert_eq!(state.cursor_timestamp, Some(5520000));
+        assert!(!state.handle_event(&mouse(MouseKind::Up, 22, 2), &keymap));
+        // outside of the candles
+        assert!(!state.handle_event(&mouse(MouseKind::Down, 0, 2), &keymap));
+        assert!(!state.handle_event(&mouse(MouseKind::Drag, 5, 2), &keymap));
+
+        render(&mut state);
+        assert!(!state.handle_event(&mouse(MouseKind::Moved, 13, 0), &keymap));
+        state.toggle_crosshair();
+        assert!(state.handle_event(&mouse(MouseKind::Moved, 13, 0), &keymap));
+        assert_eq!(state.crosshair().map(|c| c.timestamp), Some(4560000));
+    }
+}
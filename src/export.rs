@@ -0,0 +1,406 @@
+use ratatui::{
+    buffer::{Buffer, Cell},
+    layout::Rect,
+    style::{Color, Modifier},
+    widgets::StatefulWidget,
+};
+
+use crate::{CandleStickChart, CandleStickChartState};
+
+const FONT_SIZE: f64 = 14.;
+const CELL_WIDTH: f64 = FONT_SIZE * 0.6;
+const CELL_HEIGHT: f64 = FONT_SIZE * 1.2;
+const DEFAULT_FOREGROUND: &str = "#e5e5e5";
+const DEFAULT_BACKGROUND: &str = "#1e1e1e";
+
+/// Render the chart into a buffer of the given size without a terminal
+pub fn render_to_buffer(
+    chart: CandleStickChart,
+    width: u16,
+    height: u16,
+    state: &mut CandleStickChartState,
+) -> Buffer {
+    let area = Rect::new(0, 0, width, height);
+    let mut buffer = Buffer::empty(area);
+    chart.render(area, &mut buffer, state);
+    buffer
+}
+
+/// Render the chart into plain text, one line per row without trailing spaces
+pub fn render_to_string(chart: CandleStickChart, width: u16, height: u16) -> String {
+    let buffer = render_to_buffer(chart, width, height, &mut CandleStickChartState::default());
+    buffer_to_string(&buffer)
+}
+
+/// Render the chart into text with ANSI escape codes for colors, e.g. to print it on CLI
+pub fn render_to_ansi_string(chart: CandleStickChart, width: u16, height: u16) -> String {
+    let buffer = render_to_buffer(chart, width, height, &mut CandleStickChartState::default());
+    buffer_to_ansi_string(&buffer)
+}
+
+pub fn buffer_to_string(buffer: &Buffer) -> String {
+    rows(buffer)
+        .map(|row| {
+            row.iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn buffer_to_ansi_string(buffer: &Buffer) -> String {
+    let mut result = String::new();
+    for (y, row) in rows(buffer).enumerate() {
+        if y > 0 {
+            result.push('\n');
+        }
+
+        let mut prev: Option<(Color, Color, Modifier)> = None;
+        for cell in row {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if prev != Some(style) {
+                result.push_str(&sgr(cell));
+                prev = Some(style);
+            }
+            result.push_str(cell.symbol());
+        }
+        result.push_str("\x1b[0m");
+    }
+
+    result
+}
+
+/// Render the chart into a standalone SVG image
+pub fn render_to_svg(chart: CandleStickChart, width: u16, height: u16) -> String {
+    let buffer = render_to_buffer(chart, width, height, &mut CandleStickChartState::default());
+    buffer_to_svg(&buffer)
+}
+
+/// Render the chart into a standalone HTML document with a `<pre>` of colored spans
+pub fn render_to_html(chart: CandleStickChart, width: u16, height: u16) -> String {
+    let buffer = render_to_buffer(chart, width, height, &mut CandleStickChartState::default());
+    buffer_to_html(&buffer)
+}
+
+pub fn buffer_to_svg(buffer: &Buffer) -> String {
+    let width = buffer.area.width as f64 * CELL_WIDTH;
+    let height = buffer.area.height as f64 * CELL_HEIGHT;
+
+    let mut backgrounds = String::new();
+    let mut texts = String::new();
+    for (y, row) in rows(buffer).enumerate() {
+        let mut x = 0;
+        for run in runs(row) {
+            let (fg, bg) = colors(&run[0]);
+            let left = x as f64 * CELL_WIDTH;
+            let top = y as f64 * CELL_HEIGHT;
+            let run_width = run.len() as f64 * CELL_WIDTH;
+            x += run.len();
+
+            if bg != DEFAULT_BACKGROUND {
+                backgrounds.push_str(&format!(
+                    "<rect x=\"{left}\" y=\"{top}\" width=\"{run_width}\" height=\"{CELL_HEIGHT}\" fill=\"{bg}\"/>\n"
+                ));
+            }
+
+            let text = run.iter().map(|cell| cell.symbol()).collect::<String>();
+            if text.trim().is_empty() {
+                continue;
+            }
+            let baseline = top + CELL_HEIGHT * 0.8;
+            texts.push_str(&format!(
+                "<text x=\"{left}\" y=\"{baseline}\" textLength=\"{run_width}\" lengthAdjust=\"spacingAndGlyphs\" fill=\"{fg}\"{}>{}</text>\n",
+                font_attributes(run[0].modifier, true),
+                escape(&text),
+            ));
+        }
+    }
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{DEFAULT_BACKGROUND}\"/>\n\
+         <g font-family=\"monospace\" font-size=\"{FONT_SIZE}\" xml:space=\"preserve\">\n\
+         {backgrounds}{texts}</g>\n\
+         </svg>\n"
+    )
+}
+
+pub fn buffer_to_html(buffer: &Buffer) -> String {
+    let mut body = String::new();
+    for (y, row) in rows(buffer).enumerate() {
+        if y > 0 {
+            body.push('\n');
+        }
+        for run in runs(row) {
+            let (fg, bg) = colors(&run[0]);
+            let text = run.iter().map(|cell| cell.symbol()).collect::<String>();
+            body.push_str(&format!(
+                "<span style=\"color:{fg};background:{bg}{}\">{}</span>",
+                font_attributes(run[0].modifier, false),
+                escape(&text),
+            ));
+        }
+    }
+
+    format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head><meta charset=\"utf-8\"></head>\n\
+         <body>\n\
+         <pre style=\"font-family:monospace;line-height:1.2;color:{DEFAULT_FOREGROUND};background:{DEFAULT_BACKGROUND}\">{body}</pre>\n\
+         </body>\n\
+         </html>\n"
+    )
+}
+
+pub(crate) fn rows(buffer: &Buffer) -> impl Iterator<Item = &[Cell]> {
+    buffer.content.chunks(buffer.area.width.max(1) as usize)
+}
+
+/// consecutive cells with the same style
+fn runs(row: &[Cell]) -> impl Iterator<Item = &[Cell]> {
+    row.chunk_by(|a, b| (a.fg, a.bg, a.modifier) == (b.fg, b.bg, b.modifier))
+}
+
+/// foreground/background colors in hex, swapped if reversed
+fn colors(cell: &Cell) -> (String, String) {
+    let fg = hex(cell.fg).unwrap_or(DEFAULT_FOREGROUND.to_string());
+    let bg = hex(cell.bg).unwrap_or(DEFAULT_BACKGROUND.to_string());
+    if cell.modifier.contains(Modifier::REVERSED) {
+        (bg, fg)
+    } else {
+        (fg, bg)
+    }
+}
+
+/// SVG attributes or CSS declarations of the modifiers
+fn font_attributes(modifier: Modifier, svg: bool) -> String {
+    let mut result = String::new();
+    for (_, name, value) in [
+        (Modifier::BOLD, "font-weight", "bold"),
+        (Modifier::DIM, "opacity", "0.5"),
+        (Modifier::ITALIC, "font-style", "italic"),
+        (Modifier::UNDERLINED, "text-decoration", "underline"),
+        (Modifier::CROSSED_OUT, "text-decoration", "line-through"),
+    ]
+    .into_iter()
+    .filter(|(m, _, _)| modifier.contains(*m))
+    {
+        if svg {
+            result.push_str(&format!(" {}=\"{}\"", name, value));
+        } else {
+            result.push_str(&format!(";{}:{}", name, value));
+        }
+    }
+    result
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// xterm default palette
+fn hex(color: Color) -> Option<String> {
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Black => (0, 0, 0),
+        Color::Red => (205, 0, 0),
+        Color::Green => (0, 205, 0),
+        Color::Yellow => (205, 205, 0),
+        Color::Blue => (0, 0, 238),
+        Color::Magenta => (205, 0, 205),
+        Color::Cyan => (0, 205, 205),
+        Color::Gray => (229, 229, 229),
+        Color::DarkGray => (127, 127, 127),
+        Color::LightRed => (255, 0, 0),
+        Color::LightGreen => (0, 255, 0),
+        Color::LightYellow => (255, 255, 0),
+        Color::LightBlue => (92, 92, 255),
+        Color::LightMagenta => (255, 0, 255),
+        Color::LightCyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Indexed(i) => return hex(indexed(i)),
+        Color::Rgb(r, g, b) => (r, g, b),
+    };
+
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// 256 color index to named or rgb color
+fn indexed(i: u8) -> Color {
+    const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::Gray,
+        Color::DarkGray,
+        Color::LightRed,
+        Color::LightGreen,
+        Color::LightYellow,
+        Color::LightBlue,
+        Color::LightMagenta,
+        Color::LightCyan,
+        Color::White,
+    ];
+    let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+
+    match i {
+        0..=15 => NAMED[i as usize],
+        16..=231 => {
+            let i = i - 16;
+            Color::Rgb(level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (i - 232) * 10;
+            Color::Rgb(v, v, v)
+        }
+    }
+}
+
+/// select graphic rendition sequence setting the whole style of the cell
+fn sgr(cell: &Cell) -> String {
+    let mut codes = vec![String::from("0")];
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if cell.modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color_code(cell.fg, false));
+    codes.extend(color_code(cell.bg, true));
+
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base.to_string(),
+        Color::Red => (base + 1).to_string(),
+        Color::Green => (base + 2).to_string(),
+        Color::Yellow => (base + 3).to_string(),
+        Color::Blue => (base + 4).to_string(),
+        Color::Magenta => (base + 5).to_string(),
+        Color::Cyan => (base + 6).to_string(),
+        Color::Gray => (base + 7).to_string(),
+        Color::DarkGray => (base + 60).to_string(),
+        Color::LightRed => (base + 61).to_string(),
+        Color::LightGreen => (base + 62).to_string(),
+        Color::LightYellow => (base + 63).to_string(),
+        Color::LightBlue => (base + 64).to_string(),
+        Color::LightMagenta => (base + 65).to_string(),
+        Color::LightCyan => (base + 66).to_string(),
+        Color::White => (base + 67).to_string(),
+        Color::Indexed(i) => format!("{};5;{}", base + 8, i),
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+    };
+
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::{Color, Modifier, Style},
+    };
+
+    use super::{buffer_to_html, buffer_to_svg, render_to_ansi_string, render_to_string};
+    use crate::{Candle, CandleStickChart, Interval, Theme};
+
+    #[test]
+    fn plain() {
+        let chart = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap()])
+            .now(0);
+        assert_eq!(
+            render_to_string(chart, 30, 8),
+            [
+                "     3.000 ├                 │",
+                "           │                 │",
+                "           │                 ┃",
+                "           │                 │",
+                "     0.600 ├                 │",
+                "           └─────────────────┴",
+                "             *1970/01/01 00:00",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn ansi() {
+        let theme = Theme {
+            bullish: Color::Green,
+            ..Theme::monochrome()
+        };
+        let chart = CandleStickChart::new(Interval::OneMinute)
+            .candles(vec![Candle::new(0, 0.9, 3.0, 0.0, 2.1).unwrap()])
+            .theme(theme);
+        let rendered = render_to_ansi_string(chart, 14, 4);
+        let first_line = rendered.lines().next().unwrap();
+        assert_eq!(first_line, "\x1b[0m     3.000 ├ \x1b[0;32m╽\x1b[0m");
+    }
+
+    #[test]
+    fn svg() {
+        let mut buffer = Buffer::with_lines(vec!["a<b ", "  ┃ "]);
+        buffer.set_style(
+            Rect::new(2, 1, 1, 1),
+            Style::default().fg(Color::Rgb(1, 2, 3)),
+        );
+        buffer.set_style(
+            Rect::new(0, 0, 1, 1),
+            Style::default().bg(Color::Indexed(196)),
+        );
+
+        let svg = buffer_to_svg(&buffer);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"33.6\""));
+        assert!(
+            svg.contains("<rect x=\"0\" y=\"0\" width=\"8.4\" height=\"16.8\" fill=\"#ff0000\"/>")
+        );
+        assert!(svg.contains(">&lt;b </text>"));
+        assert!(svg.contains("fill=\"#010203\">┃</text>"));
+    }
+
+    #[test]
+    fn html() {
+        let mut buffer = Buffer::with_lines(vec!["a&b", "┃  "]);
+        buffer.set_style(
+            Rect::new(0, 1, 1, 1),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let html = buffer_to_html(&buffer);
+        assert!(html.contains(
+            "<pre style=\"font-family:monospace;line-height:1.2;color:#e5e5e5;background:#1e1e1e\">\
+             <span style=\"color:#e5e5e5;background:#1e1e1e\">a&amp;b</span>\n\
+             <span style=\"color:#00cd00;background:#1e1e1e;font-weight:bold\">┃</span>\
+             <span style=\"color:#e5e5e5;background:#1e1e1e\">  </span></pre>"
+        ));
+    }
+}
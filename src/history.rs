@@ -0,0 +1,219 @@
+#[cfg(feature = "async")]
+use std::future::Future;
+
+use crate::{Candle, Interval};
+
+pub type HistoryRequestId = u64;
+
+/// Candles missing before the oldest loaded candle, `start <= timestamp < end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistoryRequest {
+    pub id: HistoryRequestId,
+    pub interval: Interval,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Blocking provider of older candles, driven by `CandleStickChartState::load_history`
+pub trait CandleSource {
+    type Error;
+
+    /// candles of the requested range, an empty result marks the beginning of the history
+    fn fetch(&mut self, request: &HistoryRequest) -> Result<Vec<Candle>, Self::Error>;
+}
+
+/// Non-blocking provider of older candles, awaited by `CandleStickChartState::load_history_async`.
+/// Or fetch the request returned by `CandleStickChartState::next_history_request` on your runtime
+/// and report the result with `finish_history_request` or `cancel_history_request`.
+#[cfg(feature = "async")]
+pub trait AsyncCandleSource {
+    type Error;
+
+    /// candles of the requested range, an empty result marks the beginning of the history
+    fn fetch(
+        &self,
+        request: &HistoryRequest,
+    ) -> impl Future<Output = Result<Vec<Candle>, Self::Error>>;
+}
+
+/// Paging of older candles, at most one request is in flight
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct History {
+    pub pending: Option<HistoryRequest>,
+    pub next_id: HistoryRequestId,
+    /// start of the oldest finished request, never requested again
+    pub loaded_from: Option<i64>,
+    /// an empty response reached the beginning of the history
+    pub exhausted: bool,
+}
+
+impl History {
+    /// registers a request for the missing range unless one is in flight or it was loaded
+    pub fn request(
+        &mut self,
+        interval: Interval,
+        start: i64,
+        first_timestamp: i64,
+    ) -> Option<HistoryRequest> {
+        if self.pending.is_some() || self.exhausted {
+            return None;
+        }
+        let start = interval.align(start);
+        let end = self.loaded_from.map_or(first_timestamp, |loaded_from| {
+            loaded_from.min(first_timestamp)
+        });
+        if start >= end {
+            return None;
+        }
+
+        let request = HistoryRequest {
+            id: self.next_id,
+            interval,
+            start,
+            end,
+        };
+        self.next_id += 1;
+        self.pending = Some(request);
+        Some(request)
+    }
+
+    /// the pending request with the id, stale ids are ignored
+    pub fn take(&mut self, id: HistoryRequestId) -> Option<HistoryRequest> {
+        match self.pending {
+            Some(request) if request.id == id => self.pending.take(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CandleSource, HistoryRequest};
+    use crate::{
+        buffer_to_string, render_to_buffer, Candle, CandleStickChart, CandleStickChartState,
+        Interval,
+    };
+
+    /// one candle per minute since `oldest`
+    struct Minutes {
+        oldest: i64,
+        requests: Vec<(i64, i64)>,
+    }
+
+    impl CandleSource for Minutes {
+        type Error = ();
+
+        fn fetch(&mut self, request: &HistoryRequest) -> Result<Vec<Candle>, ()> {
+            self.requests.push((request.start, request.end));
+            Ok((request.start.max(self.oldest)..request.end)
+                .step_by(60000)
+                .map(|t| Candle::new(t, 1., 2., 0., 1.).unwrap())
+                .collect())
+        }
+    }
+
+    #[cfg(feature = "async")]
+    impl super::AsyncCandleSource for std::cell::RefCell<Minutes> {
+        type Error = ();
+
+        async fn fetch(&self, request: &HistoryRequest) -> Result<Vec<Candle>, ()> {
+            self.borrow_mut().fetch(request)
+        }
+    }
+
+    fn chart(candles: &[Candle]) -> CandleStickChart {
+        CandleStickChart::new(Interval::OneMinute).candles(candles.to_vec())
+    }
+
+    #[test]
+    fn test_request() {
+        let candles = vec![Candle::new(600000, 1., 2., 0., 1.).unwrap()];
+        let mut state = CandleStickChartState::default();
+        render_to_buffer(chart(&candles), 40, 10, &mut state);
+
+        // 27 columns ending at the candle, and a screen before them
+        let request = state.next_history_request().unwrap();
+        assert_eq!((request.start, request.end), (-2580000, 600000));
+        assert_eq!(state.next_history_request(), None);
+
+        let buffer = render_to_buffer(chart(&candles), 40, 10, &mut state);
+        assert!(buffer_to_string(&buffer).contains("loading…"));
+
+        // a stale id does not finish the pending request
+        state.finish_history_request(request.id + 1, 0);
+        assert!(state.is_loading_history());
+        state.cancel_history_request(request.id);
+        assert!(!state.is_loading_history());
+        let request = state.next_history_request().unwrap();
+        state.finish_history_request(request.id, 1);
+        assert_eq!(state.next_history_request(), None);
+    }
+
+    #[test]
+    fn test_load_history() {
+        let mut candles = vec![Candle::new(6000000, 1., 2., 0., 1.).unwrap()];
+        let mut state = CandleStickChartState::default();
+        let mut source = Minutes {
+            oldest: 4800000,
+            requests: Vec::new(),
+        };
+
+        // scroll back a screen at a time
+        for _ in 0..10 {
+            render_to_buffer(chart(&candles), 20, 10, &mut state);
+            for _ in 0..7 {
+                state.try_move_backward();
+            }
+            render_to_buffer(chart(&candles), 20, 10, &mut state);
+            state.load_history(&mut source, &mut candles).unwrap();
+        }
+
+        assert_eq!(
+            source.requests,
+            vec![(5220000, 6000000), (4800000, 5220000), (4380000, 4800000)]
+        );
+        assert_eq!(candles.len(), 21);
+        assert!(!state.is_loading_history());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_load_history_async() {
+        use futures::executor::block_on;
+
+        let mut candles = vec![Candle::new(6000000, 1., 2., 0., 1.).unwrap()];
+        let mut state = CandleStickChartState::default();
+        let source = std::cell::RefCell::new(Minutes {
+            oldest: 5700000,
+            requests: Vec::new(),
+        });
+
+        // nothing is requested before a render
+        assert_eq!(
+            block_on(state.load_history_async(&source, &mut candles)),
+            Ok(0)
+        );
+        render_to_buffer(chart(&candles), 20, 10, &mut state);
+        assert_eq!(
+            block_on(state.load_history_async(&source, &mut candles)),
+            Ok(5)
+        );
+        // an empty response at the beginning of the history
+        render_to_buffer(chart(&candles), 20, 10, &mut state);
+        for _ in 0..7 {
+            state.try_move_backward();
+        }
+        render_to_buffer(chart(&candles), 20, 10, &mut state);
+        assert_eq!(
+            block_on(state.load_history_async(&source, &mut candles)),
+            Ok(0)
+        );
+
+        assert_eq!(
+            source.borrow().requests,
+            vec![(5220000, 6000000), (4920000, 5220000)]
+        );
+        assert_eq!(candles.len(), 6);
+        assert!(!state.is_loading_history());
+    }
+}
@@ -3,13 +3,56 @@ use ordered_float::OrderedFloat;
 mod candle;
 mod candlestick_chart;
 mod candlestick_chart_state;
+mod chart_group;
+mod clock;
+mod comparison;
+mod drawing;
+mod event;
+mod export;
+mod history;
+#[cfg(any(feature = "csv", feature = "json"))]
+mod loader;
+mod marker;
+mod measure;
+mod normalize;
+mod price_level;
+mod projection;
+mod replay;
 mod symbols;
+mod theme;
 mod x_axis;
 mod y_axis;
 
-pub use candle::Candle;
+pub use candle::{Candle, CandleError};
 pub use candlestick_chart::CandleStickChart;
 pub use candlestick_chart_state::CandleStickChartState;
-pub use x_axis::Interval;
+pub use chart_group::ChartGroup;
+pub use clock::{Clock, SystemClock};
+pub use comparison::Comparison;
+pub use drawing::{Anchor, Drawing, DrawingId, DrawingKind};
+pub use event::{Action, ChartEvent, IntoChartEvent, Key, KeyPress, Keymap, MouseInput, MouseKind};
+pub use export::{
+    buffer_to_ansi_string, buffer_to_html, buffer_to_string, buffer_to_svg, render_to_ansi_string,
+    render_to_buffer, render_to_html, render_to_string, render_to_svg,
+};
+#[cfg(feature = "async")]
+pub use history::AsyncCandleSource;
+pub use history::{CandleSource, HistoryRequest, HistoryRequestId};
+#[cfg(feature = "csv")]
+pub use loader::load_csv;
+#[cfg(feature = "json")]
+pub use loader::load_json;
+#[cfg(any(feature = "csv", feature = "json"))]
+pub use loader::{
+    Column, LoadError, LoadOptions, LoadReport, RejectReason, RejectedRow, TimestampFormat,
+};
+pub use marker::{Marker, MarkerSide};
+pub use measure::Measurement;
+pub use normalize::{normalize_candles, DuplicatePolicy};
+pub use price_level::{LineStyle, PriceLevel};
+pub use replay::Replay;
+pub use theme::Theme;
+pub use x_axis::{Interval, InvalidInterval};
+pub use y_axis::{AutoFit, YAxisPlacement};
 
 pub(crate) type Float = OrderedFloat<f64>;
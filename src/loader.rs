@@ -0,0 +1,700 @@
+use std::{error::Error, fmt, io};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+
+use crate::{Candle, CandleError};
+
+/// numeric timestamps below this are seconds, it is 1973-03-03 in milliseconds
+const SECONDS_THRESHOLD: f64 = 100_000_000_000.;
+
+/// header name / object key, or zero based index
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    Name(String),
+    Index(usize),
+}
+
+impl From<&str> for Column {
+    fn from(name: &str) -> Self {
+        Column::Name(name.to_string())
+    }
+}
+
+impl From<usize> for Column {
+    fn from(index: usize) -> Self {
+        Column::Index(index)
+    }
+}
+
+impl std::str::FromStr for Column {
+    type Err = std::convert::Infallible;
+
+    /// digits are an index, anything else a name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse() {
+            Ok(index) => Column::Index(index),
+            Err(_) => Column::Name(s.to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// numbers are seconds or milliseconds by magnitude, strings are ISO 8601
+    Auto,
+    Seconds,
+    Milliseconds,
+    /// RFC 3339, or `%Y-%m-%d[ %H:%M[:%S]]` in UTC
+    Iso8601,
+    /// chrono format string, e.g. `%d/%m/%Y %H:%M`, without offset it is UTC
+    Pattern(String),
+}
+
+impl std::str::FromStr for TimestampFormat {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "auto" => TimestampFormat::Auto,
+            "s" | "seconds" => TimestampFormat::Seconds,
+            "ms" | "milliseconds" => TimestampFormat::Milliseconds,
+            "iso" | "iso8601" | "rfc3339" => TimestampFormat::Iso8601,
+            pattern => TimestampFormat::Pattern(pattern.to_string()),
+        })
+    }
+}
+
+impl TimestampFormat {
+    /// unix timestamp in milliseconds
+    fn parse(&self, value: &str) -> Option<i64> {
+        let value = value.trim();
+        let number = || value.parse::<f64>().ok().filter(|n| n.is_finite());
+        match self {
+            TimestampFormat::Auto => match number() {
+                Some(n) if n.abs() < SECONDS_THRESHOLD => Some((n * 1000.) as i64),
+                Some(n) => Some(n as i64),
+                None => parse_iso8601(value),
+            },
+            TimestampFormat::Seconds => number().map(|n| (n * 1000.) as i64),
+            TimestampFormat::Milliseconds => number().map(|n| n as i64),
+            TimestampFormat::Iso8601 => parse_iso8601(value),
+            TimestampFormat::Pattern(pattern) => DateTime::parse_from_str(value, pattern)
+                .map(|datetime| datetime.timestamp_millis())
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(value, pattern)
+                        .map(|datetime| datetime.and_utc().timestamp_millis())
+                })
+                .or_else(|_| NaiveDate::parse_from_str(value, pattern).map(date_millis))
+                .ok(),
+        }
+    }
+}
+
+fn parse_iso8601(value: &str) -> Option<i64> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(datetime.timestamp_millis());
+    }
+    [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ]
+    .into_iter()
+    .find_map(|pattern| NaiveDateTime::parse_from_str(value, pattern).ok())
+    .map(|datetime| datetime.and_utc().timestamp_millis())
+    .or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .map(date_millis)
+    })
+}
+
+fn date_millis(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+        .timestamp_millis()
+}
+
+/// How rows are turned into candles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// timestamp, open, high, low, close
+    pub(crate) columns: [Option<Column>; 5],
+    pub(crate) timestamp_format: TimestampFormat,
+    pub(crate) delimiter: u8,
+    pub(crate) has_header: bool,
+    pub(crate) repair: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            columns: Default::default(),
+            timestamp_format: TimestampFormat::Auto,
+            delimiter: b',',
+            has_header: true,
+            repair: false,
+        }
+    }
+}
+
+/// common names of the columns, matched case-insensitively when a column is not set
+const COLUMN_NAMES: [&[&str]; 5] = [
+    &[
+        "timestamp",
+        "time",
+        "open_time",
+        "opentime",
+        "date",
+        "datetime",
+        "t",
+    ],
+    &["open", "o"],
+    &["high", "h"],
+    &["low", "l"],
+    &["close", "c"],
+];
+
+const FIELDS: [&str; 5] = ["timestamp", "open", "high", "low", "close"];
+
+impl LoadOptions {
+    pub fn timestamp_column(mut self, column: impl Into<Column>) -> Self {
+        self.columns[0] = Some(column.into());
+        self
+    }
+
+    pub fn open_column(mut self, column: impl Into<Column>) -> Self {
+        self.columns[1] = Some(column.into());
+        self
+    }
+
+    pub fn high_column(mut self, column: impl Into<Column>) -> Self {
+        self.columns[2] = Some(column.into());
+        self
+    }
+
+    pub fn low_column(mut self, column: impl Into<Column>) -> Self {
+        self.columns[3] = Some(column.into());
+        self
+    }
+
+    pub fn close_column(mut self, column: impl Into<Column>) -> Self {
+        self.columns[4] = Some(column.into());
+        self
+    }
+
+    pub fn timestamp_format(mut self, timestamp_format: TimestampFormat) -> Self {
+        self.timestamp_format = timestamp_format;
+        self
+    }
+
+    /// csv field delimiter, `,` by default
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// whether the first csv record is a header, `true` by default
+    pub fn has_header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// clamp open and close into high and low instead of rejecting the row
+    pub fn repair(mut self, repair: bool) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    /// indices of the columns, unset columns fall back to common names and then to the
+    /// binance kline order
+    fn indices(&self, header: Option<&[String]>) -> Result<[usize; 5], LoadError> {
+        let find = |name: &str| {
+            header?
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(name))
+        };
+
+        let mut result = [0; 5];
+        for (i, column) in self.columns.iter().enumerate() {
+            result[i] = match column {
+                Some(Column::Index(index)) => *index,
+                Some(Column::Name(name)) => {
+                    find(name).ok_or_else(|| LoadError::ColumnNotFound(name.clone()))?
+                }
+                None => COLUMN_NAMES[i]
+                    .iter()
+                    .find_map(|name| find(name))
+                    .unwrap_or(i),
+            };
+        }
+
+        Ok(result)
+    }
+
+    /// converts the raw fields of a row, in the order of `FIELDS`
+    fn candle(&self, fields: [Option<String>; 5]) -> Result<Candle, RejectReason> {
+        let mut values = [""; 5];
+        for (i, field) in fields.iter().enumerate() {
+            values[i] = field
+                .as_deref()
+                .ok_or(RejectReason::MissingColumn(FIELDS[i]))?;
+        }
+
+        let timestamp = self
+            .timestamp_format
+            .parse(values[0])
+            .ok_or_else(|| RejectReason::InvalidTimestamp(values[0].to_string()))?;
+
+        let mut prices = [0.; 4];
+        for (i, price) in prices.iter_mut().enumerate() {
+            let value = values[i + 1];
+            *price =
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .ok_or_else(|| RejectReason::InvalidNumber {
+                        column: FIELDS[i + 1],
+                        value: value.to_string(),
+                    })?;
+        }
+
+        let [open, high, low, close] = prices;
+        if self.repair {
+            Candle::try_new_repaired(timestamp, open, high, low, close)
+        } else {
+            Candle::try_new(timestamp, open, high, low, close)
+        }
+        .map_err(RejectReason::InvalidCandle)
+    }
+}
+
+/// Why a row was not turned into a candle
+#[derive(Debug, Clone, PartialEq)]
+pub enum RejectReason {
+    MissingColumn(&'static str),
+    InvalidTimestamp(String),
+    InvalidNumber {
+        column: &'static str,
+        value: String,
+    },
+    InvalidCandle(CandleError),
+    /// the row is neither a record, an array nor an object
+    Malformed(String),
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectReason::MissingColumn(column) => write!(f, "missing {} column", column),
+            RejectReason::InvalidTimestamp(value) => write!(f, "invalid timestamp `{}`", value),
+            RejectReason::InvalidNumber { column, value } => {
+                write!(f, "invalid {} `{}`", column, value)
+            }
+            RejectReason::InvalidCandle(e) => write!(f, "{}", e),
+            RejectReason::Malformed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedRow {
+    /// 1-based line of csv or json lines, 1-based element of a json array
+    pub line: usize,
+    pub reason: RejectReason,
+}
+
+/// Candles sorted by timestamp, and everything which was wrong with the input
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LoadReport {
+    pub candles: Vec<Candle>,
+    pub rejected: Vec<RejectedRow>,
+    /// lines of rows whose timestamp was already loaded, the first row wins
+    pub duplicates: Vec<usize>,
+    /// lines of rows whose timestamp is before the previous row
+    pub unsorted: Vec<usize>,
+}
+
+impl LoadReport {
+    pub fn is_clean(&self) -> bool {
+        self.rejected.is_empty() && self.duplicates.is_empty() && self.unsorted.is_empty()
+    }
+
+    fn from_rows(rows: Vec<(usize, Result<Candle, RejectReason>)>) -> Self {
+        let mut report = LoadReport::default();
+        let mut candles = Vec::new();
+        let mut previous = None;
+        for (line, row) in rows {
+            match row {
+                Ok(candle) => {
+                    if previous.is_some_and(|previous| candle.timestamp < previous) {
+                        report.unsorted.push(line);
+                    }
+                    previous = Some(candle.timestamp);
+                    candles.push((line, candle));
+                }
+                Err(reason) => report.rejected.push(RejectedRow { line, reason }),
+            }
+        }
+
+        // stable, so that the first row of a timestamp is kept
+        candles.sort_by_key(|(_, candle)| candle.timestamp);
+        for (line, candle) in candles {
+            if report
+                .candles
+                .last()
+                .is_some_and(|last: &Candle| last.timestamp == candle.timestamp)
+            {
+                report.duplicates.push(line);
+            } else {
+                report.candles.push(candle);
+            }
+        }
+        report.duplicates.sort_unstable();
+
+        report
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} candles", self.candles.len())?;
+        if !self.rejected.is_empty() {
+            write!(f, ", {} rejected", self.rejected.len())?;
+        }
+        if !self.duplicates.is_empty() {
+            write!(f, ", {} duplicates", self.duplicates.len())?;
+        }
+        if !self.unsorted.is_empty() {
+            write!(f, ", {} unsorted", self.unsorted.len())?;
+        }
+        for row in &self.rejected {
+            write!(f, "\nline {}: {}", row.line, row.reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors which stop loading entirely, bad rows are reported in `LoadReport` instead
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    ColumnNotFound(String),
+    Parse(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::ColumnNotFound(name) => write!(f, "column `{}` not found", name),
+            LoadError::Parse(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+/// Load candles from csv, columns are found by header names or indices
+#[cfg(feature = "csv")]
+pub fn load_csv(reader: impl io::Read, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .has_headers(options.has_header)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let header = if options.has_header {
+        let header = reader.headers().map_err(csv_error)?;
+        Some(header.iter().map(str::to_string).collect::<Vec<_>>())
+    } else {
+        None
+    };
+    let indices = options.indices(header.as_deref())?;
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let line = record
+            .position()
+            .map_or(0, |position| position.line() as usize);
+        let fields = indices.map(|i| record.get(i).map(str::to_string));
+        rows.push((line, options.candle(fields)));
+    }
+
+    Ok(LoadReport::from_rows(rows))
+}
+
+#[cfg(feature = "csv")]
+fn csv_error(e: csv::Error) -> LoadError {
+    if e.is_io_error() {
+        match e.into_kind() {
+            csv::ErrorKind::Io(e) => LoadError::Io(e),
+            _ => unreachable!(),
+        }
+    } else {
+        LoadError::Parse(e.to_string())
+    }
+}
+
+/// Load candles from a json array of rows, from json lines or from a single row, a row is either
+/// an array like binance klines or an object
+#[cfg(feature = "json")]
+pub fn load_json(input: &str, options: &LoadOptions) -> Result<LoadReport, LoadError> {
+    use serde_json::Value;
+
+    let rows = match serde_json::from_str::<Value>(input) {
+        Ok(Value::Array(rows)) if rows.iter().all(|row| row.is_array() || row.is_object()) => rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| (i + 1, row))
+            .collect(),
+        Ok(Value::Array(_)) | Err(_) => input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line)
+                    .map(|row| (i + 1, row))
+                    .map_err(|e| LoadError::Parse(format!("line {}: {}", i + 1, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        // a single row, e.g. json lines of one line, reported at the line it starts on
+        Ok(row @ Value::Object(_)) => {
+            let line = input.lines().position(|line| !line.trim().is_empty());
+            vec![(line.map_or(1, |i| i + 1), row)]
+        }
+        Ok(_) => return Err(LoadError::Parse("expected an array of rows".to_string())),
+    };
+
+    let array_indices = options.indices(None)?;
+    let field = |value: Option<&Value>| match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    };
+
+    let mut result = Vec::new();
+    for (line, row) in rows {
+        let candle = match &row {
+            Value::Array(values) => options.candle(array_indices.map(|i| field(values.get(i)))),
+            Value::Object(values) => {
+                let mut fields: [Option<String>; 5] = Default::default();
+                for (i, column) in options.columns.iter().enumerate() {
+                    let names = match column {
+                        Some(Column::Name(name)) => vec![name.as_str()],
+                        Some(Column::Index(_)) => {
+                            return Err(LoadError::Parse(
+                                "indices cannot select keys of json objects".to_string(),
+                            ))
+                        }
+                        None => COLUMN_NAMES[i].to_vec(),
+                    };
+                    fields[i] = names.into_iter().find_map(|name| {
+                        let (_, value) = values
+                            .iter()
+                            .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))?;
+                        field(Some(value))
+                    });
+                }
+                options.candle(fields)
+            }
+            _ => Err(RejectReason::Malformed(
+                "expected an array or an object".to_string(),
+            )),
+        };
+        result.push((line, candle));
+    }
+
+    Ok(LoadReport::from_rows(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadOptions, LoadReport, RejectReason, RejectedRow, TimestampFormat};
+    use crate::{Candle, CandleError};
+
+    #[test]
+    fn test_timestamp_format() {
+        let auto = TimestampFormat::Auto;
+        assert_eq!(auto.parse("1703976900"), Some(1703976900000));
+        assert_eq!(auto.parse("1703976900000"), Some(1703976900000));
+        assert_eq!(auto.parse("2023-12-30T22:55:00Z"), Some(1703976900000));
+        assert_eq!(auto.parse("2023-12-30T23:55:00+01:00"), Some(1703976900000));
+        assert_eq!(auto.parse("2023-12-30 22:55"), Some(1703976900000));
+        assert_eq!(auto.parse("2023-12-30"), Some(1703894400000));
+        assert_eq!(auto.parse("yesterday"), None);
+
+        assert_eq!(
+            TimestampFormat::Seconds.parse("1703976900.5"),
+            Some(1703976900500)
+        );
+        let pattern = TimestampFormat::Pattern("%d/%m/%Y %H:%M".to_string());
+        assert_eq!(pattern.parse("30/12/2023 22:55"), Some(1703976900000));
+    }
+
+    #[test]
+    fn test_report() {
+        let candle = |timestamp| Candle::new(timestamp, 1., 2., 0., 1.).unwrap();
+        let high_below_low = CandleError::HighBelowLow { high: 0., low: 2. };
+        let report = LoadReport::from_rows(vec![
+            (1, Ok(candle(2))),
+            (2, Ok(candle(1))),
+            (3, Err(RejectReason::InvalidCandle(high_below_low))),
+            (4, Ok(candle(3))),
+            (5, Ok(candle(2))),
+        ]);
+
+        assert_eq!(report.candles, vec![candle(1), candle(2), candle(3)]);
+        assert_eq!(
+            report.rejected,
+            vec![RejectedRow {
+                line: 3,
+                reason: RejectReason::InvalidCandle(high_below_low)
+            }]
+        );
+        assert_eq!(report.duplicates, vec![5]);
+        assert_eq!(report.unsorted, vec![2, 5]);
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.to_string(),
+            "3 candles, 1 rejected, 1 duplicates, 2 unsorted\nline 3: high 0 is below low 2"
+        );
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_load_csv() {
+        use super::load_csv;
+
+        let input = "\
+Date;Open;High;Low;Close;Volume
+2023-12-30 22:55;42184.08;42191.01;42184.07;42191.01;1
+2023-12-30 22:56;42191.01;42191.01;42190.96;42190.97;1
+2023-12-30 22:57;42190.96;42123.95;42190.97;42124.50;1
+2023-12-30 22:58;42124.51;abc;42124.50;42158.65;1
+2023-12-30 22:59;42158.65
+";
+        let report = load_csv(input.as_bytes(), &LoadOptions::default().delimiter(b';')).unwrap();
+        assert_eq!(
+            report
+                .candles
+                .iter()
+                .map(|c| c.timestamp)
+                .collect::<Vec<_>>(),
+            vec![1703976900000, 1703976960000]
+        );
+        assert_eq!(
+            report.rejected,
+            vec![
+                RejectedRow {
+                    line: 4,
+                    reason: RejectReason::InvalidCandle(CandleError::HighBelowLow {
+                        high: 42123.95,
+                        low: 42190.97
+                    })
+                },
+                RejectedRow {
+                    line: 5,
+                    reason: RejectReason::InvalidNumber {
+                        column: "high",
+                        value: "abc".to_string()
+                    }
+                },
+                RejectedRow {
+                    line: 6,
+                    reason: RejectReason::MissingColumn("high")
+                },
+            ]
+        );
+
+        let options = LoadOptions::default().has_header(false).close_column(2);
+        let report = load_csv("1,1,3,0\n2,1,4,0".as_bytes(), &options).unwrap();
+        assert_eq!(
+            report.candles,
+            vec![
+                Candle::new(1000, 1., 3., 0., 3.).unwrap(),
+                Candle::new(2000, 1., 4., 0., 4.).unwrap()
+            ]
+        );
+
+        let options = LoadOptions::default().has_header(false);
+        let report = load_csv("1,5,4,0,1".as_bytes(), &options).unwrap();
+        assert!(report.candles.is_empty());
+        let report = load_csv("1,5,4,0,1".as_bytes(), &options.repair(true)).unwrap();
+        assert_eq!(
+            report.candles,
+            vec![Candle::new(1000, 4., 4., 0., 1.).unwrap()]
+        );
+
+        let options = LoadOptions::default().open_column("first");
+        assert!(load_csv("time,open\n".as_bytes(), &options).is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_json() {
+        use super::load_json;
+
+        let klines = r#"[
+            [1703976960000, "42191.01", "42191.01", "42190.96", "42190.97", "1.5"],
+            [1703976900000, "42184.08", "42191.01", "42184.07", "42191.01", "2.5"]
+        ]"#;
+        let report = load_json(klines, &LoadOptions::default()).unwrap();
+        assert_eq!(
+            report.candles,
+            vec![
+                Candle::new(1703976900000, 42184.08, 42191.01, 42184.07, 42191.01).unwrap(),
+                Candle::new(1703976960000, 42191.01, 42191.01, 42190.96, 42190.97).unwrap(),
+            ]
+        );
+        assert_eq!(report.unsorted, vec![2]);
+
+        let objects = r#"
+            {"t": "2023-12-30T22:55:00Z", "o": 1, "h": 2, "l": 0, "c": 1}
+            {"t": "2023-12-30T22:56:00Z", "o": 1, "h": 2, "l": 0}
+            "#;
+        let report = load_json(objects, &LoadOptions::default()).unwrap();
+        assert_eq!(
+            report.candles,
+            vec![Candle::new(1703976900000, 1., 2., 0., 1.).unwrap()]
+        );
+        assert_eq!(
+            report.rejected,
+            vec![RejectedRow {
+                line: 3,
+                reason: RejectReason::MissingColumn("close")
+            }]
+        );
+
+        // json lines of a single row
+        let object = "\n{\"t\": 0, \"o\": 1, \"h\": 2, \"l\": 0, \"c\": 1}\n";
+        let report = load_json(object, &LoadOptions::default()).unwrap();
+        assert_eq!(
+            report.candles,
+            vec![Candle::new(0, 1., 2., 0., 1.).unwrap()]
+        );
+        let report = load_json("\n{\"t\": 0}", &LoadOptions::default()).unwrap();
+        assert_eq!(report.rejected[0].line, 2);
+
+        assert!(load_json("{\"t\": 1", &LoadOptions::default()).is_err());
+        assert!(load_json("1", &LoadOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use ordered_float::OrderedFloat;
+use ratatui::style::Color;
+
+use crate::{symbols::*, Float, Theme};
+
+/// Where a marker is placed when it has no price
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerSide {
+    /// above the high of the candle
+    #[default]
+    Above,
+    /// below the low of the candle
+    Below,
+}
+
+/// Color of a marker without its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tone {
+    Neutral,
+    Bullish,
+    Bearish,
+}
+
+/// Glyph drawn on the candle which contains `timestamp`, e.g. fills, earnings or liquidations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Marker {
+    pub(crate) timestamp: i64,
+    pub(crate) price: Option<Float>,
+    pub(crate) side: MarkerSide,
+    pub(crate) glyph: String,
+    /// the color of the tone in the theme if `None`
+    color: Option<Color>,
+    tone: Tone,
+}
+
+impl Marker {
+    pub fn new(timestamp: i64, side: MarkerSide) -> Self {
+        let glyph = match side {
+            MarkerSide::Above => MARKER_ABOVE,
+            MarkerSide::Below => MARKER_BELOW,
+        };
+
+        Self {
+            timestamp,
+            price: None,
+            side,
+            glyph: glyph.to_string(),
+            color: None,
+            tone: Tone::Neutral,
+        }
+    }
+
+    /// `▲` below the candle in the bullish color of the theme
+    pub fn buy(timestamp: i64) -> Self {
+        Self {
+            tone: Tone::Bullish,
+            ..Self::new(timestamp, MarkerSide::Below)
+        }
+    }
+
+    /// `▼` above the candle in the bearish color of the theme
+    pub fn sell(timestamp: i64) -> Self {
+        Self {
+            tone: Tone::Bearish,
+            ..Self::new(timestamp, MarkerSide::Above)
+        }
+    }
+
+    /// draw at the row of the price instead of above/below the candle
+    pub fn price(mut self, price: f64) -> Self {
+        self.price = Some(OrderedFloat::from(price));
+        self
+    }
+
+    pub fn glyph(mut self, glyph: impl Into<String>) -> Self {
+        self.glyph = glyph.into();
+        self
+    }
+
+    /// instead of the color of the theme
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub(crate) fn color_in(&self, theme: &Theme) -> Color {
+        self.color.unwrap_or(match self.tone {
+            Tone::Neutral => Color::Reset,
+            Tone::Bullish => theme.bullish,
+            Tone::Bearish => theme.bearish,
+        })
+    }
+
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn side(&self) -> MarkerSide {
+        self.side
+    }
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, FixedOffset};
+
+use crate::{y_axis::Numeric, Candle, Float};
+
+/// Change between two candles selected with `CandleStickChartState::start_measure`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    /// timestamp of the earlier candle
+    pub start_timestamp: i64,
+    /// timestamp of the later candle
+    pub end_timestamp: i64,
+    /// close price of the later candle minus close price of the earlier candle
+    pub delta: Float,
+    /// `delta` in percent of the close price of the earlier candle
+    pub percent: Float,
+    /// number of bars from the earlier candle to the later candle
+    pub bars: i64,
+}
+
+impl Measurement {
+    pub(crate) fn new(start: &Candle, end: &Candle, interval_millis: i64) -> Self {
+        let delta = end.close - start.close;
+        let percent = if start.close == 0. {
+            Float::from(0.)
+        } else {
+            delta / start.close * 100.
+        };
+
+        Self {
+            start_timestamp: start.timestamp,
+            end_timestamp: end.timestamp,
+            delta,
+            percent,
+            bars: (end.timestamp - start.timestamp) / interval_millis,
+        }
+    }
+
+    pub fn elapsed_millis(&self) -> i64 {
+        self.end_timestamp - self.start_timestamp
+    }
+
+    /// lines of the info box, the delta is formatted like the prices on the y axis
+    pub(crate) fn render(&self, numeric: &Numeric, time_offset: FixedOffset) -> Vec<String> {
+        let format_time = |timestamp| {
+            DateTime::from_timestamp_millis(timestamp)
+                .map(|t| {
+                    t.with_timezone(&time_offset)
+                        .format("%m/%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default()
+        };
+
+        vec![
+            format!(
+                "{}{} ({:+.2}%)",
+                if *self.delta >= 0. { "+" } else { "" },
+                numeric.format(self.delta).trim(),
+                self.percent
+            ),
+            format!(
+                "{} bars, {}",
+                self.bars,
+                format_duration(self.elapsed_millis())
+            ),
+            format!(
+                "{} → {}",
+                format_time(self.start_timestamp),
+                format_time(self.end_timestamp)
+            ),
+        ]
+    }
+}
+
+/// `1d 2h 3m 4s` omitting zero units
+fn format_duration(millis: i64) -> String {
+    let seconds = millis / 1000;
+    let units = [
+        (seconds / 86400, "d"),
+        (seconds % 86400 / 3600, "h"),
+        (seconds % 3600 / 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let rendered = units
+        .iter()
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect::<Vec<_>>();
+
+    if rendered.is_empty() {
+        String::from("0s")
+    } else {
+        rendered.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Offset, Utc};
+
+    use super::{format_duration, Measurement};
+    use crate::{y_axis::Numeric, Candle};
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(90_000), "1m 30s");
+        assert_eq!(format_duration(93_600_000), "1d 2h");
+    }
+
+    #[test]
+    fn test_render() {
+        let start = Candle::new(0, 100., 110., 90., 100.).unwrap();
+        let end = Candle::new(900_000, 100., 130., 100., 125.).unwrap();
+        let measurement = Measurement::new(&start, &end, 60_000);
+        assert_eq!(
+            measurement.render(&Numeric::default(), Utc.fix()),
+            vec![
+                "+25.000 (+25.00%)",
+                "15 bars, 15m",
+                "01/01 00:00 → 01/01 00:15"
+            ]
+        );
+
+        // with the precision of the chart
+        let measurement = Measurement::new(&end, &start, 60_000);
+        assert_eq!(
+            measurement.render(&Numeric::new(8, 1), Utc.fix())[0],
+            "-25.0 (-20.00%)"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::cmp::{max, min};
+
+use itertools::Itertools;
+
+use crate::{Candle, Interval};
+
+/// Which candle is drawn when several candles share a timestamp
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    KeepFirst,
+    /// the latest update of a candle wins, e.g. when appending a streamed candle
+    #[default]
+    KeepLast,
+    /// open of the first, close of the last, the highest high and the lowest low
+    Merge,
+}
+
+/// Candles sorted by timestamp with unique timestamps. Duplicates are resolved in the given order,
+/// and candles not starting at a bucket of the interval are dropped if `drop_unaligned` is set.
+pub fn normalize_candles(
+    candles: impl IntoIterator<Item = Candle>,
+    interval: Interval,
+    duplicates: DuplicatePolicy,
+    drop_unaligned: bool,
+) -> Vec<Candle> {
+    candles
+        .into_iter()
+        .filter(|candle| !drop_unaligned || interval.is_aligned(candle.timestamp))
+        // stable, so that duplicates keep their order
+        .sorted_by_key(|candle| candle.timestamp)
+        .coalesce(|a, b| {
+            if a.timestamp != b.timestamp {
+                return Err((a, b));
+            }
+            Ok(match duplicates {
+                DuplicatePolicy::KeepFirst => a,
+                DuplicatePolicy::KeepLast => b,
+                DuplicatePolicy::Merge => Candle {
+                    timestamp: a.timestamp,
+                    open: a.open,
+                    high: max(a.high, b.high),
+                    low: min(a.low, b.low),
+                    close: b.close,
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_candles, DuplicatePolicy};
+    use crate::{Candle, Interval};
+
+    fn candles() -> Vec<Candle> {
+        vec![
+            Candle::new(120000, 5., 6., 4., 5.).unwrap(),
+            Candle::new(0, 1., 2., 0., 1.).unwrap(),
+            Candle::new(60000, 1., 3., 1., 2.).unwrap(),
+            Candle::new(60000, 2., 4., 0.5, 3.).unwrap(),
+            Candle::new(90000, 7., 8., 6., 7.).unwrap(),
+            Candle::new(60000, 3., 3., 2., 2.5).unwrap(),
+        ]
+    }
+
+    fn normalize(duplicates: DuplicatePolicy, drop_unaligned: bool) -> Vec<Candle> {
+        normalize_candles(candles(), Interval::OneMinute, duplicates, drop_unaligned)
+    }
+
+    #[test]
+    fn test_sort() {
+        let timestamps = normalize(DuplicatePolicy::KeepLast, false)
+            .iter()
+            .map(|c| c.timestamp)
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![0, 60000, 90000, 120000]);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        assert_eq!(
+            normalize(DuplicatePolicy::KeepFirst, true)[1],
+            Candle::new(60000, 1., 3., 1., 2.).unwrap()
+        );
+        assert_eq!(
+            normalize(DuplicatePolicy::KeepLast, true)[1],
+            Candle::new(60000, 3., 3., 2., 2.5).unwrap()
+        );
+        assert_eq!(
+            normalize(DuplicatePolicy::Merge, true)[1],
+            Candle::new(60000, 1., 4., 0.5, 2.5).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_drop_unaligned() {
+        let timestamps = normalize(DuplicatePolicy::KeepLast, true)
+            .iter()
+            .map(|c| c.timestamp)
+            .collect::<Vec<_>>();
+        assert_eq!(timestamps, vec![0, 60000, 120000]);
+    }
+}
@@ -0,0 +1,65 @@
+use ordered_float::OrderedFloat;
+use ratatui::style::Color;
+
+use crate::{symbols::*, Float};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl LineStyle {
+    pub(crate) fn symbol(&self) -> &'static str {
+        match self {
+            LineStyle::Solid => LEVEL_SOLID,
+            LineStyle::Dashed => LEVEL_DASHED,
+            LineStyle::Dotted => LEVEL_DOTTED,
+        }
+    }
+}
+
+/// Horizontal line at a price, e.g. open order, average entry, stop-loss or take-profit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceLevel {
+    pub(crate) price: Float,
+    pub(crate) label: String,
+    pub(crate) color: Color,
+    pub(crate) line_style: LineStyle,
+    pub(crate) show_offscreen: bool,
+}
+
+impl PriceLevel {
+    pub fn new(price: f64) -> Self {
+        Self {
+            price: OrderedFloat::from(price),
+            label: String::default(),
+            color: Color::Reset,
+            line_style: LineStyle::default(),
+            show_offscreen: false,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn line_style(mut self, line_style: LineStyle) -> Self {
+        self.line_style = line_style;
+        self
+    }
+
+    /// indicate the level with an arrow at the top/bottom edge while it is out of the y range
+    pub fn show_offscreen(mut self, show_offscreen: bool) -> Self {
+        self.show_offscreen = show_offscreen;
+        self
+    }
+}
@@ -0,0 +1,142 @@
+use ratatui::prelude::Rect;
+
+use crate::{y_axis::YAxis, Float, Interval};
+
+/// bound of relative cells, far enough to keep the slope of any line crossing the area while
+/// leaving room for arithmetic on them
+const LIMIT: i64 = 1 << 40;
+
+/// Maps timestamps and prices to cells of the chart data area
+pub(crate) struct Projection<'a> {
+    pub area: Rect,
+    /// timestamp of the first column
+    pub start_timestamp: i64,
+    pub interval: Interval,
+    pub y_axis: &'a YAxis,
+}
+
+impl<'a> Projection<'a> {
+    /// column relative to the area, can be out of the area
+    pub fn column(&self, timestamp: i64) -> i64 {
+        timestamp
+            .saturating_sub(self.start_timestamp)
+            .div_euclid(self.interval.as_millis())
+            .clamp(-LIMIT, LIMIT)
+    }
+
+    /// row relative to the area, can be out of the area
+    pub fn row(&self, price: Float) -> i64 {
+        let y = (*self.y_axis.calc_y(price))
+            .floor()
+            .clamp(-LIMIT as f64, LIMIT as f64);
+        self.y_axis.height() as i64 - 1 - y as i64
+    }
+
+    /// absolute position of the relative cell if it is in the area
+    pub fn position(&self, column: i64, row: i64) -> Option<(u16, u16)> {
+        if (0..self.area.width as i64).contains(&column)
+            && (0..self.area.height as i64).contains(&row)
+        {
+            Some((self.area.x + column as u16, self.area.y + row as u16))
+        } else {
+            None
+        }
+    }
+
+    /// cells on the line between two relative cells, clipped to the area
+    pub fn line(&self, from: (i64, i64), to: (i64, i64)) -> Vec<(u16, u16)> {
+        let Some(((x0, y0), (x1, y1))) = self.clip(from, to) else {
+            return Vec::new();
+        };
+
+        // Bresenham's line algorithm
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let (mut x, mut y) = (x0, y0);
+        let mut result = Vec::new();
+        loop {
+            result.extend(self.position(x, y));
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+
+        result
+    }
+
+    /// Liang-Barsky clipping, so that far away anchors do not cost walking every cell
+    fn clip(&self, from: (i64, i64), to: (i64, i64)) -> Option<((i64, i64), (i64, i64))> {
+        let (x0, y0) = (from.0 as f64, from.1 as f64);
+        let (dx, dy) = (to.0 as f64 - x0, to.1 as f64 - y0);
+        let (max_x, max_y) = (self.area.width as f64 - 1., self.area.height as f64 - 1.);
+
+        let mut t0: f64 = 0.;
+        let mut t1: f64 = 1.;
+        for (p, q) in [(-dx, x0), (dx, max_x - x0), (-dy, y0), (dy, max_y - y0)] {
+            if p == 0. {
+                if q < 0. {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if p < 0. {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+
+        let at = |t: f64| ((x0 + t * dx).round() as i64, (y0 + t * dy).round() as i64);
+        Some((at(t0), at(t1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::prelude::Rect;
+
+    use super::Projection;
+    use crate::{
+        y_axis::{Numeric, YAxis},
+        Interval,
+    };
+
+    #[test]
+    fn test_line() {
+        let y_axis = YAxis::new(Numeric::default(), 4, 0.into(), 4.into());
+        let projection = Projection {
+            area: Rect::new(10, 10, 4, 4),
+            start_timestamp: 0,
+            interval: Interval::OneSecond,
+            y_axis: &y_axis,
+        };
+
+        assert_eq!(projection.column(3000), 3);
+        assert_eq!(projection.row(1.into()), 2);
+        assert_eq!(
+            projection.line((0, 3), (3, 0)),
+            vec![(10, 13), (11, 12), (12, 11), (13, 10)]
+        );
+        assert_eq!(
+            projection.line((-1000, 1), (1000, 1)),
+            vec![(10, 11), (11, 11), (12, 11), (13, 11)]
+        );
+        assert_eq!(projection.line((-5, -5), (-1, 10)), vec![]);
+    }
+}
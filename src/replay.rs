@@ -0,0 +1,261 @@
+use std::{
+    cmp::{max, min},
+    time::Duration,
+};
+
+use ordered_float::OrderedFloat;
+
+use crate::{Candle, Float, Interval};
+
+/// Plays a recorded series back on a simulated clock, revealing prices up to `now` as candles of
+/// the chart interval with a partially formed last candle.
+///
+/// Pass `candles()` and `now()` to `CandleStickChart` on every frame, and `advance` by the elapsed
+/// wall time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    interval: Interval,
+    /// price path sorted by timestamp
+    ticks: Vec<(i64, Float)>,
+    /// number of revealed ticks
+    revealed: usize,
+    candles: Vec<Candle>,
+    now: i64,
+    speed: f64,
+    paused: bool,
+}
+
+impl Replay {
+    pub const MIN_SPEED: f64 = 1.;
+    pub const MAX_SPEED: f64 = 100.;
+
+    /// recorded candles of `source_interval` are played as open, low, high, close (or open,
+    /// high, low, close if bearish) spread over their duration
+    pub fn from_candles(
+        candles: impl IntoIterator<Item = Candle>,
+        source_interval: Interval,
+        interval: Interval,
+    ) -> Self {
+        let step = source_interval.as_millis() / 4;
+        let ticks = candles
+            .into_iter()
+            .filter(|candle| candle.is_renderable())
+            .flat_map(|candle| {
+                let (first, second) = if candle.close >= candle.open {
+                    (candle.low, candle.high)
+                } else {
+                    (candle.high, candle.low)
+                };
+                [candle.open, first, second, candle.close]
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(i, price)| (candle.timestamp + step * i as i64, price))
+            })
+            .collect();
+        Self::new(ticks, interval)
+    }
+
+    /// recorded trades as timestamp and price, non-finite prices and timestamps which cannot be
+    /// rendered are skipped
+    pub fn from_trades(trades: impl IntoIterator<Item = (i64, f64)>, interval: Interval) -> Self {
+        let ticks = trades
+            .into_iter()
+            .filter(|(timestamp, price)| {
+                price.is_finite() && Candle::is_renderable_timestamp(*timestamp)
+            })
+            .map(|(timestamp, price)| (timestamp, OrderedFloat::from(price)))
+            .collect();
+        Self::new(ticks, interval)
+    }
+
+    fn new(mut ticks: Vec<(i64, Float)>, interval: Interval) -> Self {
+        ticks.sort_by_key(|(timestamp, _)| *timestamp);
+        let mut replay = Self {
+            interval,
+            now: ticks.first().map_or(0, |(timestamp, _)| *timestamp),
+            ticks,
+            revealed: 0,
+            candles: Vec::new(),
+            speed: Self::MIN_SPEED,
+            paused: false,
+        };
+        replay.reveal();
+        replay
+    }
+
+    /// revealed candles, the last one is still forming unless the replay is finished
+    pub fn candles(&self) -> &[Candle] {
+        &self.candles
+    }
+
+    /// simulated time in milliseconds
+    pub fn now(&self) -> i64 {
+        self.now
+    }
+
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// clamped to `Replay::MIN_SPEED..=Replay::MAX_SPEED`
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = if speed.is_nan() {
+            Self::MIN_SPEED
+        } else {
+            speed.clamp(Self::MIN_SPEED, Self::MAX_SPEED)
+        };
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.revealed == self.ticks.len()
+    }
+
+    /// move the simulated clock by the wall time times the speed, unless paused
+    pub fn advance(&mut self, elapsed: Duration) {
+        if self.paused || self.is_finished() {
+            return;
+        }
+        let delta = (elapsed.as_secs_f64() * 1000. * self.speed) as i64;
+        self.seek(self.now.saturating_add(delta));
+    }
+
+    /// complete the forming candle, or the next one if it is complete
+    pub fn step(&mut self) {
+        let interval = self.interval.as_millis();
+        let end = self.interval.align(self.now).saturating_add(interval - 1);
+        self.seek(if end > self.now {
+            end
+        } else {
+            end.saturating_add(interval)
+        });
+    }
+
+    /// move the simulated clock to the timestamp, going back replays from the start
+    pub fn seek(&mut self, timestamp: i64) {
+        if timestamp < self.now {
+            self.revealed = 0;
+            self.candles.clear();
+        }
+        self.now = timestamp;
+        self.reveal();
+    }
+
+    fn reveal(&mut self) {
+        while let Some(&(timestamp, price)) = self.ticks.get(self.revealed) {
+            if timestamp > self.now {
+                break;
+            }
+            self.revealed += 1;
+
+            let bucket = self.interval.align(timestamp);
+            match self.candles.last_mut() {
+                Some(candle) if candle.timestamp == bucket => {
+                    candle.high = max(candle.high, price);
+                    candle.low = min(candle.low, price);
+                    candle.close = price;
+                }
+                _ => self.candles.push(Candle {
+                    timestamp: bucket,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Replay;
+    use crate::{Candle, Interval};
+
+    fn recorded() -> Vec<Candle> {
+        vec![
+            Candle::new(60000, 2., 4., 1., 3.).unwrap(),
+            Candle::new(0, 1., 2., 0., 1.5).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_forming_candle() {
+        let mut replay = Replay::from_candles(recorded(), Interval::OneMinute, Interval::OneMinute);
+        assert_eq!(replay.now(), 0);
+        assert_eq!(replay.candles(), &[Candle::new(0, 1., 1., 1., 1.).unwrap()]);
+
+        replay.advance(Duration::from_secs(20));
+        assert_eq!(replay.candles(), &[Candle::new(0, 1., 1., 0., 0.).unwrap()]);
+
+        replay.pause();
+        replay.advance(Duration::from_secs(20));
+        assert_eq!(replay.now(), 20000);
+
+        replay.resume();
+        replay.set_speed(1000.);
+        assert_eq!(replay.speed(), 100.);
+        replay.advance(Duration::from_secs(1));
+        assert_eq!(
+            replay.candles(),
+            &[
+                Candle::new(0, 1., 2., 0., 1.5).unwrap(),
+                Candle::new(60000, 2., 4., 1., 3.).unwrap(),
+            ]
+        );
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn test_step_and_seek() {
+        let mut replay =
+            Replay::from_candles(recorded(), Interval::OneMinute, Interval::FiveMinutes);
+        replay.step();
+        assert_eq!(replay.now(), 299999);
+        assert_eq!(replay.candles(), &[Candle::new(0, 1., 4., 0., 3.).unwrap()]);
+
+        replay.seek(59999);
+        assert_eq!(
+            replay.candles(),
+            &[Candle::new(0, 1., 2., 0., 1.5).unwrap()]
+        );
+
+        let mut replay = Replay::from_trades(
+            [(1000, 1.), (61000, 3.), (31000, f64::NAN), (30000, 2.)],
+            Interval::OneMinute,
+        );
+        replay.step();
+        assert_eq!(replay.candles(), &[Candle::new(0, 1., 2., 1., 2.).unwrap()]);
+        replay.step();
+        assert_eq!(replay.candles().len(), 2);
+        assert!(replay.is_finished());
+
+        // out of range timestamps are skipped and the clock saturates
+        let mut replay = Replay::from_trades([(i64::MIN, 1.), (0, 2.)], Interval::OneWeek);
+        assert_eq!(replay.now(), 0);
+        replay.seek(i64::MAX);
+        replay.step();
+        assert_eq!(replay.now(), i64::MAX);
+    }
+}
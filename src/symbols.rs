@@ -7,3 +7,55 @@ pub const UNICODE_HALF_BODY_BOTTOM: &str = "╻";
 pub const UNICODE_HALF_WICK_BOTTOM: &str = "╷";
 pub const UNICODE_HALF_BODY_TOP: &str = "╹";
 pub const UNICODE_HALF_WICK_TOP: &str = "╵";
+pub const UNICODE_CLIPPED_UP: &str = "↑";
+pub const UNICODE_CLIPPED_DOWN: &str = "↓";
+
+pub const GRID_HORIZONTAL: &str = "┈";
+pub const GRID_VERTICAL: &str = "┊";
+pub const GRID_CROSS: &str = "┼";
+
+pub const LEVEL_SOLID: &str = "─";
+pub const LEVEL_DASHED: &str = "╌";
+pub const LEVEL_DOTTED: &str = "┄";
+pub const LEVEL_ABOVE: &str = "↑";
+pub const LEVEL_BELOW: &str = "↓";
+
+pub const CROSSHAIR_HORIZONTAL: &str = "╌";
+pub const CROSSHAIR_VERTICAL: &str = "╎";
+pub const CROSSHAIR_CROSS: &str = "┼";
+
+/// symbols drawn beneath candles, which are kept under the empty part of candle columns
+pub const BACKGROUND_SYMBOLS: [&str; 7] = [
+    GRID_HORIZONTAL,
+    GRID_VERTICAL,
+    GRID_CROSS,
+    LEVEL_SOLID,
+    LEVEL_DASHED,
+    LEVEL_DOTTED,
+    CROSSHAIR_VERTICAL,
+];
+
+/// shown before the first candle while older candles are loaded
+pub const LOADING: &str = "loading…";
+pub const NO_DATA: &str = "no data";
+
+pub const MARKER_ABOVE: &str = "▼";
+pub const MARKER_BELOW: &str = "▲";
+
+pub const DRAWING_HORIZONTAL: &str = "─";
+pub const DRAWING_VERTICAL: &str = "│";
+pub const DRAWING_RISING: &str = "╱";
+pub const DRAWING_FALLING: &str = "╲";
+pub const DRAWING_DOTTED: &str = "┄";
+pub const DRAWING_TOP_LEFT: &str = "┌";
+pub const DRAWING_TOP_RIGHT: &str = "┐";
+pub const DRAWING_BOTTOM_LEFT: &str = "└";
+pub const DRAWING_BOTTOM_RIGHT: &str = "┘";
+
+pub const COMPARISON_POINT: &str = "•";
+pub const COMPARISON_LINE: &str = "·";
+pub const COMPARISON_LEGEND: &str = "━";
+
+pub const AXIS_TICK: char = '┴';
+pub const AXIS_LINES: &str = "├┤│└┘─┴";
+pub const AXIS_REALTIME: char = '*';
@@ -0,0 +1,85 @@
+use ratatui::style::Color;
+
+/// Colors used to draw every part of the chart.
+///
+/// `Color::Reset` means "use the terminal default".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// axis lines and tick marks (`├`, `│`, `└`, `─`, `┴`)
+    pub axis: Color,
+    /// y axis values and x axis time labels
+    pub label: Color,
+    /// realtime marker (`*`) on the x axis
+    pub realtime: Color,
+    /// background grid lines
+    pub grid: Color,
+    /// background of the whole widget area
+    pub background: Color,
+    /// crosshair lines
+    pub crosshair: Color,
+    /// background of the measured range
+    pub selection: Color,
+    /// candle that closed lower than it opened
+    pub bearish: Color,
+    /// candle that closed higher than (or same as) it opened
+    pub bullish: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            axis: Color::Reset,
+            label: Color::Reset,
+            realtime: Color::Yellow,
+            grid: Color::DarkGray,
+            background: Color::Reset,
+            crosshair: Color::Gray,
+            selection: Color::Rgb(40, 48, 64),
+            bearish: Color::Rgb(234, 74, 90),
+            bullish: Color::Rgb(52, 208, 88),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            axis: Color::Rgb(87, 96, 106),
+            label: Color::Rgb(36, 41, 47),
+            realtime: Color::Rgb(154, 103, 0),
+            grid: Color::Rgb(208, 215, 222),
+            background: Color::Rgb(255, 255, 255),
+            crosshair: Color::Rgb(110, 119, 129),
+            selection: Color::Rgb(221, 235, 255),
+            bearish: Color::Rgb(207, 34, 46),
+            bullish: Color::Rgb(26, 127, 55),
+        }
+    }
+
+    /// blue/orange pair which stays distinguishable with red-green color blindness
+    pub fn colorblind() -> Self {
+        Self {
+            bearish: Color::Rgb(230, 159, 0),
+            bullish: Color::Rgb(0, 114, 178),
+            ..Self::dark()
+        }
+    }
+
+    pub fn monochrome() -> Self {
+        Self {
+            axis: Color::Reset,
+            label: Color::Reset,
+            realtime: Color::Reset,
+            grid: Color::DarkGray,
+            background: Color::Reset,
+            crosshair: Color::Gray,
+            selection: Color::DarkGray,
+            bearish: Color::DarkGray,
+            bullish: Color::White,
+        }
+    }
+}
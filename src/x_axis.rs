@@ -1,6 +1,13 @@
-use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use std::{error::Error, fmt, str::FromStr};
+
+use chrono::{DateTime, Duration, FixedOffset, TimeZone};
 use itertools::Itertools;
 
+use crate::{
+    candle::{MAX_TIMESTAMP, MIN_TIMESTAMP},
+    symbols::{AXIS_REALTIME, AXIS_TICK},
+};
+
 enum Precision {
     Second,
     Minute,
@@ -9,6 +16,11 @@ enum Precision {
 
 #[repr(i64)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "&str", try_from = "String")
+)]
 pub enum Interval {
     OneSecond = 1,
     OneMinute = 60,
@@ -28,6 +40,77 @@ pub enum Interval {
 }
 
 impl Interval {
+    /// exchange style name, e.g. `1m`, `4h`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::OneSecond => "1s",
+            Interval::OneMinute => "1m",
+            Interval::ThreeMinutes => "3m",
+            Interval::FiveMinutes => "5m",
+            Interval::FifteenMinutes => "15m",
+            Interval::ThirtyMinutes => "30m",
+            Interval::OneHour => "1h",
+            Interval::TwoHours => "2h",
+            Interval::FourHours => "4h",
+            Interval::SixHours => "6h",
+            Interval::EightHours => "8h",
+            Interval::TwelveHours => "12h",
+            Interval::OneDay => "1d",
+            Interval::ThreeDays => "3d",
+            Interval::OneWeek => "1w",
+        }
+    }
+
+    const ALL: [Interval; 15] = [
+        Interval::OneSecond,
+        Interval::OneMinute,
+        Interval::ThreeMinutes,
+        Interval::FiveMinutes,
+        Interval::FifteenMinutes,
+        Interval::ThirtyMinutes,
+        Interval::OneHour,
+        Interval::TwoHours,
+        Interval::FourHours,
+        Interval::SixHours,
+        Interval::EightHours,
+        Interval::TwelveHours,
+        Interval::OneDay,
+        Interval::ThreeDays,
+        Interval::OneWeek,
+    ];
+
+    /// all intervals from the shortest to the longest
+    pub fn iter() -> impl Iterator<Item = Interval> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn as_millis(&self) -> i64 {
+        *self as i64 * 1000
+    }
+
+    /// start of the bucket which contains the timestamp, weeks start on monday. Saturates at the
+    /// ends of `i64`.
+    pub fn align(&self, timestamp: i64) -> i64 {
+        let origin = self.origin();
+        timestamp
+            .saturating_sub(origin)
+            .div_euclid(self.as_millis())
+            .saturating_mul(self.as_millis())
+            .saturating_add(origin)
+    }
+
+    pub fn is_aligned(&self, timestamp: i64) -> bool {
+        self.align(timestamp) == timestamp
+    }
+
+    /// buckets are counted from the unix epoch, except that weeks start on monday 1970-01-05
+    fn origin(&self) -> i64 {
+        match self {
+            Interval::OneWeek => 4 * Interval::OneDay.as_millis(),
+            _ => 0,
+        }
+    }
+
     fn render_gap(&self) -> usize {
         match self {
             Interval::OneSecond => 30,
@@ -69,17 +152,93 @@ impl Interval {
     }
 }
 
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = InvalidInterval;
+
+    /// exchange style names, `1M` is a month and not a minute so case matters
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Interval::iter()
+            .find(|interval| interval.as_str() == s)
+            .ok_or_else(|| InvalidInterval(format!("unknown interval `{}`", s)))
+    }
+}
+
+impl From<Interval> for &'static str {
+    fn from(interval: Interval) -> Self {
+        interval.as_str()
+    }
+}
+
+impl TryFrom<String> for Interval {
+    type Error = InvalidInterval;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Interval> for Duration {
+    fn from(interval: Interval) -> Self {
+        Duration::seconds(interval as i64)
+    }
+}
+
+impl From<Interval> for std::time::Duration {
+    fn from(interval: Interval) -> Self {
+        std::time::Duration::from_secs(interval as u64)
+    }
+}
+
+impl TryFrom<Duration> for Interval {
+    type Error = InvalidInterval;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        Interval::iter()
+            .find(|interval| Duration::from(*interval) == duration)
+            .ok_or_else(|| InvalidInterval(format!("no interval of {}", duration)))
+    }
+}
+
+impl TryFrom<std::time::Duration> for Interval {
+    type Error = InvalidInterval;
+
+    fn try_from(duration: std::time::Duration) -> Result<Self, Self::Error> {
+        Interval::iter()
+            .find(|interval| std::time::Duration::from(*interval) == duration)
+            .ok_or_else(|| InvalidInterval(format!("no interval of {:?}", duration)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidInterval(String);
+
+impl fmt::Display for InvalidInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for InvalidInterval {}
+
 pub(crate) struct XAxis {
     width: u16,
     min: i64,
     max: i64,
     interval: Interval,
     is_realtime: bool,
+    /// current time in milliseconds, the last timestamp unless set
+    now: i64,
 }
 
 impl XAxis {
     pub fn new(width: u16, min: i64, max: i64, interval: Interval, is_realtime: bool) -> Self {
-        assert!(min <= max);
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
 
         Self {
             width,
@@ -87,9 +246,15 @@ impl XAxis {
             max,
             interval,
             is_realtime,
+            now: max,
         }
     }
 
+    pub fn now(mut self, now: i64) -> Self {
+        self.now = now;
+        self
+    }
+
     /// render priority
     ///
     /// 1. second diff      -> HH:MM:SS
@@ -108,10 +273,7 @@ impl XAxis {
 
         let full_timestamps = (self.min..=self.max)
             .step_by(self.interval as usize * 1000)
-            .map(|t| {
-                let naive = NaiveDateTime::from_timestamp_millis(t).unwrap();
-                (t, Utc.from_utc_datetime(&naive))
-            })
+            .map(|t| (t, DateTime::from_timestamp_millis(t).unwrap_or_default()))
             .collect_vec();
         let full_timestamps_len = full_timestamps.len();
         let timestamps = if full_timestamps_len > width {
@@ -129,24 +291,26 @@ impl XAxis {
         match timestamp_len as u64 {
             0 => {}
             1 => {
-                let now = Utc::now();
                 let (_, last) = timestamps.last().unwrap();
-                let rendered =
-                    shorted_now_string(now, *last, self.interval.render_precision(), time_offset);
-                let rendered = if self.is_realtime {
-                    format!("*{}", rendered)
-                } else {
-                    rendered
-                };
-
-                let written = overwrite_chars(
-                    &mut result[1],
-                    (timestamp_len - 1) as isize - (rendered.len() / 2) as isize,
-                    rendered,
-                    true,
-                );
+                // a clock out of range is clamped rather than read as 1970
+                let now = self.now.clamp(MIN_TIMESTAMP, MAX_TIMESTAMP);
+                let now = DateTime::from_timestamp_millis(now).unwrap_or(*last);
+                let rendered = self.realtime_label(shorted_now_string(
+                    now,
+                    *last,
+                    self.interval.render_precision(),
+                    time_offset,
+                ));
+
+                let written = !rendered.is_empty()
+                    && overwrite_chars(
+                        &mut result[1],
+                        (timestamp_len - 1) as isize - (rendered.len() / 2) as isize,
+                        rendered,
+                        true,
+                    );
                 if written {
-                    result[0][timestamp_len - 1] = '┴';
+                    result[0][timestamp_len - 1] = AXIS_TICK;
                 }
             }
             2.. => {
@@ -154,25 +318,21 @@ impl XAxis {
                 {
                     let (_, prev) = timestamps[timestamp_len - 2];
                     let (_, now) = timestamps.last().unwrap();
-                    let rendered = shorted_now_string(
+                    let rendered = self.realtime_label(shorted_now_string(
                         prev,
                         *now,
                         self.interval.render_precision(),
                         time_offset,
-                    );
-                    let rendered = if self.is_realtime {
-                        format!("*{}", rendered)
-                    } else {
-                        rendered
-                    };
-                    let written = overwrite_chars(
-                        &mut result[1],
-                        (timestamp_len - 1) as isize - (rendered.len() / 2) as isize,
-                        rendered,
-                        true,
-                    );
+                    ));
+                    let written = !rendered.is_empty()
+                        && overwrite_chars(
+                            &mut result[1],
+                            (timestamp_len - 1) as isize - (rendered.len() / 2) as isize,
+                            rendered,
+                            true,
+                        );
                     if written {
-                        result[0][timestamp_len - 1] = '┴';
+                        result[0][timestamp_len - 1] = AXIS_TICK;
                     }
                 }
 
@@ -193,7 +353,7 @@ impl XAxis {
                     );
 
                     if written {
-                        result[0][idx + 1] = '┴';
+                        result[0][idx + 1] = AXIS_TICK;
                     }
                 }
             }
@@ -201,6 +361,15 @@ impl XAxis {
 
         result.into_iter().map(String::from_iter).collect()
     }
+
+    /// the label of the last timestamp, marked while realtime unless there is no label to mark
+    fn realtime_label(&self, label: String) -> String {
+        if self.is_realtime && !label.is_empty() {
+            format!("{}{}", AXIS_REALTIME, label)
+        } else {
+            label
+        }
+    }
 }
 
 fn shorted_now_string<Tz: TimeZone>(
@@ -315,12 +484,50 @@ fn overwrite_chars(chars: &mut Vec<char>, idx: isize, value: String, overlap: bo
 
 #[cfg(test)]
 mod tests {
-    use chrono::{Offset, Utc};
+    use chrono::{Duration, Offset, Utc};
     use itertools::Itertools;
 
     use super::XAxis;
     use crate::x_axis::{overwrite_chars, Interval};
 
+    #[test]
+    fn test_interval() {
+        for interval in Interval::iter() {
+            assert_eq!(interval.to_string().parse(), Ok(interval));
+            assert_eq!(Interval::try_from(Duration::from(interval)), Ok(interval));
+            assert_eq!(
+                Interval::try_from(std::time::Duration::from(interval)),
+                Ok(interval)
+            );
+        }
+        assert_eq!(Interval::iter().count(), 15);
+        assert_eq!("4h".parse(), Ok(Interval::FourHours));
+        assert!("1M".parse::<Interval>().is_err());
+        assert!(Interval::try_from(Duration::minutes(7)).is_err());
+        assert_eq!(
+            std::time::Duration::from(Interval::OneDay),
+            std::time::Duration::from_secs(86400)
+        );
+    }
+
+    #[test]
+    fn test_align() {
+        // 2023-12-30 22:55:42 UTC, a saturday
+        let timestamp = 1703976942000;
+        assert_eq!(Interval::OneMinute.align(timestamp), 1703976900000);
+        assert_eq!(Interval::FourHours.align(timestamp), 1703966400000);
+        assert_eq!(Interval::OneDay.align(timestamp), 1703894400000);
+        // monday 2023-12-25
+        assert_eq!(Interval::OneWeek.align(timestamp), 1703462400000);
+        assert_eq!(Interval::OneMinute.align(-1), -60000);
+        // no overflow at the ends
+        assert!(Interval::OneMinute.align(i64::MIN) <= i64::MIN + 60000);
+        assert!(Interval::OneWeek.align(i64::MIN) <= i64::MIN + Interval::OneWeek.as_millis());
+        assert!(Interval::OneWeek.align(i64::MAX) > i64::MAX - Interval::OneWeek.as_millis());
+        assert!(Interval::OneHour.is_aligned(1703966400000));
+        assert!(!Interval::OneHour.is_aligned(timestamp));
+    }
+
     #[test]
     fn test_overwrite_chars() {
         let mut str = "x".repeat(10).chars().collect_vec();
@@ -360,4 +567,33 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn render_with_now() {
+        let axis = || XAxis::new(20, 1704009600000, 1704009600000, Interval::OneMinute, false);
+        assert_eq!(
+            axis().now(1704009660000).render(Utc.fix()),
+            vec!["┴───────────────────", "08:00               "]
+        );
+        assert_eq!(
+            axis().now(1672531200000).render(Utc.fix()),
+            vec!["┴───────────────────", "12/31 08:00         "]
+        );
+        // no bare realtime marker without a label
+        assert_eq!(
+            XAxis::new(20, 1704009600000, 1704009600000, Interval::OneMinute, true)
+                .now(1704009600000)
+                .render(Utc.fix()),
+            vec!["────────────────────", "                    "]
+        );
+        // clamped rather than read as 1970
+        assert_eq!(
+            axis().now(i64::MAX).render(Utc.fix()),
+            axis().now(8_000_000_000_000_000).render(Utc.fix())
+        );
+        assert_eq!(
+            axis().now(i64::MIN).render(Utc.fix()),
+            vec!["┴───────────────────", "2023/12/31 08:00    "]
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use std::cmp::{self, max};
 
+use itertools::Itertools;
 use ordered_float::OrderedFloat;
 
 use crate::Float;
@@ -26,6 +27,87 @@ impl Numeric {
         let scale = self.scale;
         format!("{0:>precision$.scale$}", value)
     }
+
+    /// like `format` with a `%` suffix, keeping the width
+    pub fn format_percent(&self, value: Float) -> String {
+        let precision = self.precision.saturating_sub(1);
+        let scale = self.scale;
+        format!("{0:>precision$.scale$}%", value)
+    }
+}
+
+/// How the y range is fitted to the visible candles while it is not locked by
+/// `CandleStickChartState`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AutoFit {
+    padding: Float,
+    percentile: Option<Float>,
+}
+
+impl AutoFit {
+    /// extra space above the highest and below the lowest price, as a ratio of the fitted range
+    pub fn padding(mut self, padding: f64) -> Self {
+        self.padding = OrderedFloat::from(padding.max(0.));
+        self
+    }
+
+    /// fit to the given percentile(0.5 ~ 1.0) of highs and the mirrored percentile of lows
+    /// instead of the extremes, so that a single spike does not squash the other candles
+    pub fn percentile(mut self, percentile: f64) -> Self {
+        self.percentile = Some(OrderedFloat::from(percentile.clamp(0.5, 1.)));
+        self
+    }
+
+    pub(crate) fn fit(&self, lows: &[Float], highs: &[Float]) -> (Float, Float) {
+        let (min, max) = match self.percentile {
+            Some(percentile) => {
+                // floored with a tolerance for `1 - percentile` rounding down, and kept within the
+                // values so that `get` is `None` only without any
+                let clip = |len: usize| {
+                    let clip = (*(OrderedFloat::from(1.) - percentile) * len as f64 + 1e-9).floor();
+                    (clip as usize).min(len.saturating_sub(1))
+                };
+                let lows = lows.iter().cloned().sorted().collect_vec();
+                let highs = highs.iter().cloned().sorted().rev().collect_vec();
+                (
+                    lows.get(clip(lows.len())).cloned().unwrap_or_default(),
+                    highs.get(clip(highs.len())).cloned().unwrap_or_default(),
+                )
+            }
+            None => (
+                lows.iter().min().cloned().unwrap_or_default(),
+                highs.iter().max().cloned().unwrap_or_default(),
+            ),
+        };
+        let padding = (max - min) * self.padding;
+
+        (min - padding, max + padding)
+    }
+}
+
+/// Which side(s) of the chart the y axis is drawn on
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum YAxisPlacement {
+    #[default]
+    Left,
+    Right,
+    Both,
+}
+
+impl YAxisPlacement {
+    pub(crate) fn has_left(&self) -> bool {
+        matches!(self, YAxisPlacement::Left | YAxisPlacement::Both)
+    }
+
+    pub(crate) fn has_right(&self) -> bool {
+        matches!(self, YAxisPlacement::Right | YAxisPlacement::Both)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Side {
+    Left,
+    Right,
 }
 
 pub(crate) struct YAxis {
@@ -34,6 +116,8 @@ pub(crate) struct YAxis {
     min: Float,
     max: Float,
     unit: Float,
+    /// labels are the percent change from the base price if set
+    base: Option<Float>,
 }
 
 impl YAxis {
@@ -41,8 +125,18 @@ impl YAxis {
         cmp::max(numeric.format(max).len(), numeric.format(min).len()) as u16 + 4
     }
 
+    /// width of percent labels of any base, for prices whose highest is `ratio` times the lowest
+    pub fn estimated_percent_width(numeric: Numeric, ratio: Float) -> u16 {
+        let highest = (ratio - 1.) * 100.;
+        cmp::max(
+            numeric.format_percent(highest).len(),
+            numeric.format_percent(OrderedFloat::from(-100.)).len(),
+        ) as u16
+            + 4
+    }
+
     pub fn new(numeric: Numeric, height: u16, min: Float, max: Float) -> Self {
-        assert!(min <= max);
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
         let unit = (max - min) / OrderedFloat::from(height as f64);
 
         Self {
@@ -51,6 +145,21 @@ impl YAxis {
             min,
             max,
             unit,
+            base: None,
+        }
+    }
+
+    /// label values as the percent change from the base price
+    pub fn percent_of(mut self, base: Float) -> Self {
+        self.base = Some(base);
+        self
+    }
+
+    /// label of the value on the axis
+    pub fn format(&self, value: Float) -> String {
+        match self.base {
+            Some(base) => self.numeric.format_percent((value / base - 1.) * 100.),
+            None => self.numeric.format(value),
         }
     }
 
@@ -58,22 +167,49 @@ impl YAxis {
         self.height
     }
 
+    pub fn max(&self) -> Float {
+        self.max
+    }
+
+    /// rows which have value label and `├`
+    pub fn is_tick(&self, row: u16) -> bool {
+        row % 4 == 0
+    }
+
     pub fn calc_y(&self, value: Float) -> Float {
         (value - self.min) / self.unit
     }
 
-    pub fn render(&self) -> Vec<String> {
+    /// row(from the top) where the value is drawn, `None` if it is out of the y range
+    pub fn calc_row(&self, value: Float) -> Option<u16> {
+        if value < self.min || value > self.max {
+            return None;
+        }
+
+        let y = (*self.calc_y(value))
+            .floor()
+            .clamp(0., (self.height - 1) as f64);
+        Some(self.height - 1 - y as u16)
+    }
+
+    /// render like ` 3.000 ├ ` on the left side or ` ┤ 3.000 ` on the right side
+    pub fn render(&self, side: Side) -> Vec<String> {
         let mut result = Vec::new();
-        let max_chars = max(
-            self.numeric.format(self.max).len(),
-            self.numeric.format(self.min).len(),
-        );
+        let max_chars = max(self.format(self.max).len(), self.format(self.min).len());
         for i in 0..self.height {
-            let rendered = if i % 4 == 0 {
+            let rendered = if self.is_tick(i) {
                 let value = self.max - self.unit * OrderedFloat::from(i);
-                format!(" {} ├ ", self.numeric.format(value))
+                let value = format!("{:>max_chars$}", self.format(value));
+                match side {
+                    Side::Left => format!(" {} ├ ", value),
+                    Side::Right => format!(" ┤ {} ", value),
+                }
             } else {
-                format!(" {} │ ", " ".repeat(max_chars))
+                let value = " ".repeat(max_chars);
+                match side {
+                    Side::Left => format!(" {} │ ", value),
+                    Side::Right => format!(" │ {} ", value),
+                }
             };
 
             result.push(rendered);
@@ -88,7 +224,7 @@ mod tests {
     use ordered_float::OrderedFloat;
 
     use crate::{
-        y_axis::{Numeric, YAxis},
+        y_axis::{AutoFit, Numeric, Side, YAxis},
         Float,
     };
 
@@ -97,11 +233,75 @@ mod tests {
         let numeric = Numeric::new(10, 2);
         assert_eq!(numeric.format(Float::from(3.123456)), "      3.12");
         assert_eq!(numeric.format(Float::from(99991)), "  99991.00");
+        assert_eq!(numeric.format_percent(Float::from(-3.5)), "    -3.50%");
     }
 
     #[test]
     fn test_calc() {
         let y_axis = YAxis::new(Numeric::default(), 40, 100.into(), 200.into());
         assert_eq!(y_axis.calc_y(130.into()), OrderedFloat::from(12));
+        assert_eq!(y_axis.calc_row(130.into()), Some(27));
+        assert_eq!(y_axis.calc_row(200.into()), Some(0));
+        assert_eq!(y_axis.calc_row(100.into()), Some(39));
+        assert_eq!(y_axis.calc_row(99.into()), None);
+    }
+
+    #[test]
+    fn test_render_side() {
+        let y_axis = YAxis::new(Numeric::new(5, 1), 5, 100.into(), 200.into());
+        assert_eq!(
+            y_axis.render(Side::Left),
+            vec![
+                " 200.0 ├ ",
+                "       │ ",
+                "       │ ",
+                "       │ ",
+                " 120.0 ├ ",
+            ]
+        );
+        assert_eq!(
+            y_axis.render(Side::Right),
+            vec![
+                " ┤ 200.0 ",
+                " │       ",
+                " │       ",
+                " │       ",
+                " ┤ 120.0 ",
+            ]
+        );
+
+        let y_axis =
+            YAxis::new(Numeric::new(5, 1), 5, 100.into(), 200.into()).percent_of(160.into());
+        assert_eq!(
+            y_axis.render(Side::Left),
+            vec![
+                "  25.0% ├ ",
+                "        │ ",
+                "        │ ",
+                "        │ ",
+                " -25.0% ├ ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_auto_fit() {
+        let lows = (1..=10).map(|v| Float::from(v as f64)).collect::<Vec<_>>();
+        let highs = (11..=20).map(|v| Float::from(v as f64)).collect::<Vec<_>>();
+
+        assert_eq!(AutoFit::default().fit(&lows, &highs), (1.into(), 20.into()));
+        assert_eq!(
+            AutoFit::default().padding(0.5).fit(&lows, &highs),
+            (Float::from(-8.5), Float::from(29.5))
+        );
+        assert_eq!(
+            AutoFit::default().percentile(0.9).fit(&lows, &highs),
+            (2.into(), 19.into())
+        );
+
+        // a single value and a short series are not clipped away
+        let median = AutoFit::default().percentile(0.5);
+        assert_eq!(median.fit(&[1.into()], &[2.into()]), (1.into(), 2.into()));
+        assert_eq!(median.fit(&lows[..3], &highs[..3]), (2.into(), 12.into()));
     }
 }